@@ -0,0 +1,293 @@
+use crate::advanced::EnemyType;
+use crate::daily;
+use crate::event::{GameEvent, KillCause, Subscriber};
+use crate::storage;
+use egui::{Color32, Context, RichText};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const ACHIEVEMENT_FILE: &str = "achievements.toml";
+const TOAST_TIME: Duration = Duration::from_secs(4);
+const MAX_TOASTS: usize = 3;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Achievement {
+    FirstBlood,
+    TntTriple,
+    ChainReaction,
+    SpecialDeath,
+    BossSlayer,
+    HotStreak,
+    Survivor,
+    Sharpshooter,
+}
+
+pub const ALL_ACHIEVEMENTS: [Achievement; 8] = [
+    Achievement::FirstBlood,
+    Achievement::TntTriple,
+    Achievement::ChainReaction,
+    Achievement::SpecialDeath,
+    Achievement::BossSlayer,
+    Achievement::HotStreak,
+    Achievement::Survivor,
+    Achievement::Sharpshooter,
+];
+
+pub const SURVIVOR_TIME: Duration = Duration::from_secs(120);
+pub const HOT_STREAK: u32 = 25;
+pub const SHARPSHOOTER_SHOTS: u32 = 10;
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct AchievementBook {
+    #[serde(default)]
+    unlocked: BTreeMap<Achievement, u64>,
+}
+
+#[derive(Default)]
+pub struct AchievementTracker {
+    earned: Vec<Achievement>,
+    taken: usize,
+    streak: u32,
+}
+
+#[derive(Default)]
+pub struct Toasts {
+    queue: Vec<(Achievement, Instant)>,
+}
+
+impl Achievement {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "İlk Kan",
+            Achievement::TntTriple => "Üçü Bir Arada",
+            Achievement::ChainReaction => "Zincirleme Reaksiyon",
+            Achievement::SpecialDeath => "Nadir Çığlık",
+            Achievement::BossSlayer => "Dev Avcısı",
+            Achievement::HotStreak => "Seri Katil",
+            Achievement::Survivor => "Dayanıklı",
+            Achievement::Sharpshooter => "Keskin Nişancı",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "İlk düşmanını vur",
+            Achievement::TntTriple => "Tek bir TNT ile 3 düşman indir",
+            Achievement::ChainReaction => "3 TNT'lik bir zincirleme patlama başlat",
+            Achievement::SpecialDeath => "%5 ihtimalle çalan özel ölüm sesini duy",
+            Achievement::BossSlayer => "Boss'u yen",
+            Achievement::HotStreak => "Iskalamadan 25 düşman vur",
+            Achievement::Survivor => "Gelişmiş modda 2 dakika hayatta kal",
+            Achievement::Sharpshooter => "Klasik bir turu en az 10 atışla %100 isabetle bitir",
+        }
+    }
+}
+
+impl AchievementBook {
+    pub fn load() -> AchievementBook {
+        storage::load_toml(ACHIEVEMENT_FILE)
+    }
+
+    fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.unlocked.contains_key(&achievement) {
+            return false;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.unlocked.insert(achievement, now);
+        storage::save_toml(ACHIEVEMENT_FILE, self);
+        true
+    }
+
+    pub fn record(&mut self, earned: Vec<Achievement>) -> Vec<Achievement> {
+        earned
+            .into_iter()
+            .filter(|achievement| self.unlock(*achievement))
+            .collect()
+    }
+
+    fn unlocked_date(&self, achievement: Achievement) -> Option<String> {
+        let secs = self.unlocked.get(&achievement)?;
+        let (year, month, day) = daily::civil_date((secs / 86_400) as i64);
+        Some(format!("{:04}-{:02}-{:02}", year, month, day))
+    }
+}
+
+impl AchievementTracker {
+    pub fn achieve(&mut self, achievement: Achievement) {
+        if !self.earned.contains(&achievement) {
+            self.earned.push(achievement);
+        }
+    }
+
+    pub fn take(&mut self) -> Vec<Achievement> {
+        let earned = self.earned[self.taken..].to_vec();
+        self.taken = self.earned.len();
+        earned
+    }
+}
+
+impl Subscriber for AchievementTracker {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::ShotFired { hit: false, .. } => self.streak = 0,
+            GameEvent::EnemyKilled {
+                enemy_type,
+                cause,
+                special,
+                ..
+            } => {
+                if special {
+                    self.achieve(Achievement::SpecialDeath);
+                }
+                if enemy_type == EnemyType::Civilian {
+                    if cause == KillCause::Shot {
+                        self.streak = 0;
+                    }
+                    return;
+                }
+                self.achieve(Achievement::FirstBlood);
+                if cause == KillCause::Shot {
+                    self.streak += 1;
+                    if self.streak >= HOT_STREAK {
+                        self.achieve(Achievement::HotStreak);
+                    }
+                }
+            }
+            GameEvent::TntExploded { chain, kills, .. } => {
+                if kills >= 3 {
+                    self.achieve(Achievement::TntTriple);
+                }
+                if chain >= 3 {
+                    self.achieve(Achievement::ChainReaction);
+                }
+            }
+            GameEvent::BossDefeated => self.achieve(Achievement::BossSlayer),
+            _ => {}
+        }
+    }
+}
+
+impl Toasts {
+    pub fn extend(&mut self, unlocked: Vec<Achievement>) {
+        let now = Instant::now();
+        self.queue
+            .extend(unlocked.into_iter().map(|achievement| (achievement, now)));
+    }
+
+    pub fn draw(&mut self, ctx: &Context) {
+        let now = Instant::now();
+        self.queue
+            .retain(|(_, shown)| now.duration_since(*shown) < TOAST_TIME);
+        for (index, (achievement, shown)) in self.queue.iter().take(MAX_TOASTS).enumerate() {
+            let age = now.duration_since(*shown).as_secs_f32();
+            let fade = (TOAST_TIME.as_secs_f32() - age).clamp(0.0, 1.0);
+            egui::Area::new(egui::Id::new("achievement_toast").with(index))
+                .anchor(
+                    egui::Align2::RIGHT_BOTTOM,
+                    egui::vec2(-10.0, -10.0 - index as f32 * 64.0),
+                )
+                .order(egui::Order::Tooltip)
+                .interactable(false)
+                .show(ctx, |ui| {
+                    ui.set_opacity(fade);
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(260.0);
+                        ui.label(
+                            RichText::new(format!("🏆 {}", achievement.name()))
+                                .size(18.0)
+                                .color(Color32::GOLD),
+                        );
+                        ui.label(RichText::new(achievement.description()).size(13.0));
+                    });
+                });
+        }
+        if !self.queue.is_empty() {
+            ctx.request_repaint_after(Duration::from_millis(50));
+        }
+    }
+}
+
+pub fn draw_achievements(ctx: &Context, book: &AchievementBook) -> bool {
+    let mut back = false;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(30.0);
+            ui.heading(RichText::new("Başarımlar").size(36.0));
+            ui.label(
+                RichText::new(format!(
+                    "{}/{} açıldı",
+                    book.unlocked.len(),
+                    ALL_ACHIEVEMENTS.len()
+                ))
+                .size(18.0)
+                .color(Color32::GRAY),
+            );
+            ui.add_space(20.0);
+
+            egui::Grid::new("achievements")
+                .spacing([30.0, 10.0])
+                .show(ui, |ui| {
+                    for achievement in ALL_ACHIEVEMENTS {
+                        let date = book.unlocked_date(achievement);
+                        let color = if date.is_some() {
+                            Color32::GOLD
+                        } else {
+                            Color32::GRAY
+                        };
+                        ui.label(RichText::new(achievement.name()).size(18.0).color(color));
+                        ui.label(RichText::new(achievement.description()).size(14.0));
+                        ui.label(
+                            RichText::new(date.unwrap_or_else(|| "Kilitli".to_string()))
+                                .size(14.0)
+                                .color(color),
+                        );
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(30.0);
+            if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                back = true;
+            }
+        });
+    });
+
+    back
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kill(enemy_type: EnemyType, special: bool) -> GameEvent {
+        GameEvent::EnemyKilled {
+            enemy_type,
+            pos: egui::Pos2::ZERO,
+            cause: KillCause::Shot,
+            reaction: None,
+            special,
+        }
+    }
+
+    #[test]
+    fn special_civilian_death_unlocks_special_death() {
+        let mut tracker = AchievementTracker::default();
+        tracker.on_event(&kill(EnemyType::Civilian, true));
+        assert_eq!(tracker.take(), vec![Achievement::SpecialDeath]);
+    }
+
+    #[test]
+    fn civilian_kill_breaks_the_streak() {
+        let mut tracker = AchievementTracker::default();
+        for _ in 0..HOT_STREAK - 1 {
+            tracker.on_event(&kill(EnemyType::Normal, false));
+        }
+        tracker.on_event(&kill(EnemyType::Civilian, false));
+        tracker.on_event(&kill(EnemyType::Normal, false));
+        assert_eq!(tracker.take(), vec![Achievement::FirstBlood]);
+    }
+}
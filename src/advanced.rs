@@ -1,15 +1,38 @@
+use crate::achievement::{self, Achievement, AchievementTracker};
+use crate::audio::SoundBoard;
+use crate::boss::{BOSS_MAX_HP, Boss, BossHit, BossPhase, BossSnapshot, BossState};
+use crate::daily::{DailyEntry, DailyRun};
+use crate::difficulty::{DifficultyParams, Director, Settings};
+use crate::event::{EventBus, GameEvent, KillCause, Subscriber};
+use crate::level::{Level, LevelScript, MovePath, RoundStats, ScheduledSpawn, SpawnKind};
+use crate::mutator::{DOUBLE_SPEED, Mutator, MutatorSet};
+use crate::particle::Juice;
+use crate::profile::{RoundSummary, RoundTally};
+use crate::replay::{self, FrameInput, Replay, ReplayKind, ShopAction};
+use crate::resume;
+use crate::supply::{COMMON_LOOT, ELITE_LOOT, LootTable, SupplyBoxType};
+use crate::survival::{self, SurvivalBoard, SurvivalEntry};
+use crate::upgrade::{ALL_PERKS, Perk, SimParams, UpgradeStack};
 use eframe::egui;
+use egui::emath::TSTransform;
 use egui::{RichText, TextureHandle, TextureOptions};
 use image::GenericImageView;
 use image::io::Reader as ImageReader;
-use rand::Rng;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::BufReader;
 use std::time::{Duration, Instant};
 
+const WAVE_LENGTH: Duration = Duration::from_secs(30);
+const SLOW_MOTION_SPEED: f32 = 0.5;
+const DROP_ANIMATION: Duration = Duration::from_millis(350);
+const EXPIRY_WARNING: Duration = Duration::from_secs(1);
+const DEBRIS_FADE: Duration = Duration::from_millis(1500);
+
 pub struct AdvancedApp {
     score: u32,
+    score_fraction: f32,
     enemies: Vec<Enemy>,
     supply_boxes: Vec<SupplyBox>,
     next_enemy_spawn_time: Instant,
@@ -24,9 +47,48 @@ pub struct AdvancedApp {
     show_intro: bool,
     intro_start: Instant,
     restart_count: u32,
-    #[allow(dead_code)]
-    audio_stream: OutputStream,
-    audio_handle: OutputStreamHandle,
+    friendly_fire: u32,
+    boss: Option<Boss>,
+    boss_spawned: bool,
+    boss_defeated_at: Option<(Instant, u32)>,
+    script: Option<LevelScript>,
+    level_complete: bool,
+    result_taken: bool,
+    summary_taken: bool,
+    tally: RoundTally,
+    achievements: AchievementTracker,
+    events: EventBus,
+    juice: Juice,
+    streak: u32,
+    best_streak: u32,
+    upgrades: UpgradeStack,
+    params: SimParams,
+    coins: u32,
+    wave: u32,
+    wave_end: Duration,
+    shopping: bool,
+    ammo: u32,
+    reload_until: Option<Instant>,
+    shockwaves: Vec<Shockwave>,
+    chain_banner: Option<(Instant, u32)>,
+    sim_now: Instant,
+    effects: Vec<ActiveEffect>,
+    settings: Settings,
+    difficulty: DifficultyParams,
+    director: Option<Director>,
+    survival: Option<SurvivalBoard>,
+    survival_rank: Option<usize>,
+    rng: ChaCha12Rng,
+    mutators: MutatorSet,
+    daily: Option<DailyRun>,
+    missed_one_shot: bool,
+    seed: u64,
+    pointer: Option<egui::Pos2>,
+    pending_action: Option<ShopAction>,
+    recording: Option<Vec<FrameInput>>,
+    replaying: bool,
+    ghost: Option<Box<Ghost>>,
+    sounds: SoundBoard,
 }
 
 enum EnemyState {
@@ -43,31 +105,32 @@ enum EnemyState {
     Dying(Instant),
 }
 
-enum EnemyType {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EnemyType {
     Normal,
     Elite,
-}
-enum SupplyBoxType {
-    Health,
-    Tnt,
+    Civilian,
 }
 
 enum SupplyBoxState {
     Active,
+    Expiring(Instant),
     Damaged(Instant),
+    Primed { detonate_at: Instant, chain: u32 },
+    Opened(Instant),
     Exploding(Instant),
-    #[allow(dead_code)]
     Destroyed(Instant),
 }
 
 struct Enemy {
     x: f32,
     y: f32,
+    origin: egui::Pos2,
+    path: MovePath,
     texture_key: String,
     state: EnemyState,
     enemy_type: EnemyType,
     hitpoints: u32,
-    #[allow(dead_code)]
     spawn_time: Instant,
 }
 
@@ -76,255 +139,1401 @@ struct SupplyBox {
     y: f32,
     kind: SupplyBoxType,
     state: SupplyBoxState,
+    hitpoints: u32,
     spawn_time: Instant,
+    dropped_from: Option<egui::Pos2>,
+}
+
+struct Shockwave {
+    center: egui::Pos2,
+    radius: f32,
+    start: Instant,
+}
+
+struct Ghost {
+    app: AdvancedApp,
+    frames: Vec<FrameInput>,
+    index: usize,
+    clock: Duration,
+    elapsed: Duration,
+    best_score: u32,
+}
+
+struct ActiveEffect {
+    kind: SupplyBoxType,
+    until: Instant,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AdvancedSnapshot {
+    kind: RoundKind,
+    settings: Settings,
+    rng_seed: [u8; 32],
+    rng_word_pos: u64,
+    #[serde(default)]
+    seed: [u8; 8],
+    #[serde(default)]
+    recording: Option<String>,
+    score: u32,
+    #[serde(default)]
+    score_fraction: f32,
+    visible_time: i64,
+    game_time: f64,
+    started_ago: f32,
+    intro_ago: Option<f32>,
+    next_enemy_in: f32,
+    next_elite_in: f32,
+    next_supply_in: f32,
+    friendly_fire: u32,
+    boss_spawned: bool,
+    streak: u32,
+    best_streak: u32,
+    coins: u32,
+    wave: u32,
+    wave_end: f64,
+    shopping: bool,
+    ammo: u32,
+    reload_in: Option<f32>,
+    upgrades: UpgradeStack,
+    director: Option<Director>,
+    #[serde(default)]
+    tally: RoundTally,
+    boss: Option<BossSnapshot>,
+    effects: Vec<EffectSnapshot>,
+    enemies: Vec<EnemySnapshot>,
+    supply_boxes: Vec<SupplySnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum RoundKind {
+    Advanced,
+    Survival,
+    Level { source: String, cursor: usize },
+    Daily { date: String, official: bool },
+}
+
+#[derive(Serialize, Deserialize)]
+enum EnemyStateSnapshot {
+    Alive {
+        fire_in: f32,
+        fired_ago: Option<f32>,
+    },
+    Firing {
+        fired_ago: f32,
+        texture_key: String,
+    },
+    Dying(f32),
+}
+
+#[derive(Serialize, Deserialize)]
+struct EnemySnapshot {
+    x: f32,
+    y: f32,
+    origin: [f32; 2],
+    path: MovePath,
+    texture_key: String,
+    state: EnemyStateSnapshot,
+    enemy_type: EnemyType,
+    hitpoints: u32,
+    age: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SupplyStateSnapshot {
+    Active,
+    Expiring(f32),
+    Damaged(f32),
+    Primed { detonate_in: f32, chain: u32 },
+    Opened(f32),
+    Exploding(f32),
+    Destroyed(f32),
+}
+
+#[derive(Serialize, Deserialize)]
+struct SupplySnapshot {
+    x: f32,
+    y: f32,
+    kind: SupplyBoxType,
+    state: SupplyStateSnapshot,
+    hitpoints: u32,
+    age: f32,
+    dropped_from: Option<[f32; 2]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EffectSnapshot {
+    kind: SupplyBoxType,
+    remaining: f32,
+}
+
+impl SupplyBox {
+    fn new(kind: SupplyBoxType, x: f32, y: f32, now: Instant) -> Self {
+        SupplyBox {
+            x,
+            y,
+            kind,
+            state: SupplyBoxState::Active,
+            hitpoints: kind.spec().hitpoints,
+            spawn_time: now,
+            dropped_from: None,
+        }
+    }
+
+    fn snapshot(&self, now: Instant) -> SupplySnapshot {
+        let state = match self.state {
+            SupplyBoxState::Active => SupplyStateSnapshot::Active,
+            SupplyBoxState::Expiring(t) => SupplyStateSnapshot::Expiring(resume::elapsed(t, now)),
+            SupplyBoxState::Damaged(t) => SupplyStateSnapshot::Damaged(resume::elapsed(t, now)),
+            SupplyBoxState::Primed { detonate_at, chain } => SupplyStateSnapshot::Primed {
+                detonate_in: resume::remaining(detonate_at, now),
+                chain,
+            },
+            SupplyBoxState::Opened(t) => SupplyStateSnapshot::Opened(resume::elapsed(t, now)),
+            SupplyBoxState::Exploding(t) => SupplyStateSnapshot::Exploding(resume::elapsed(t, now)),
+            SupplyBoxState::Destroyed(t) => SupplyStateSnapshot::Destroyed(resume::elapsed(t, now)),
+        };
+        SupplySnapshot {
+            x: self.x,
+            y: self.y,
+            kind: self.kind,
+            state,
+            hitpoints: self.hitpoints,
+            age: resume::elapsed(self.spawn_time, now),
+            dropped_from: self.dropped_from.map(|pos| [pos.x, pos.y]),
+        }
+    }
+
+    fn restore(snapshot: SupplySnapshot, now: Instant) -> Self {
+        let state = match snapshot.state {
+            SupplyStateSnapshot::Active => SupplyBoxState::Active,
+            SupplyStateSnapshot::Expiring(secs) => SupplyBoxState::Expiring(resume::ago(secs, now)),
+            SupplyStateSnapshot::Damaged(secs) => SupplyBoxState::Damaged(resume::ago(secs, now)),
+            SupplyStateSnapshot::Primed { detonate_in, chain } => SupplyBoxState::Primed {
+                detonate_at: resume::after(detonate_in, now),
+                chain,
+            },
+            SupplyStateSnapshot::Opened(secs) => SupplyBoxState::Opened(resume::ago(secs, now)),
+            SupplyStateSnapshot::Exploding(secs) => {
+                SupplyBoxState::Exploding(resume::ago(secs, now))
+            }
+            SupplyStateSnapshot::Destroyed(secs) => {
+                SupplyBoxState::Destroyed(resume::ago(secs, now))
+            }
+        };
+        SupplyBox {
+            x: snapshot.x,
+            y: snapshot.y,
+            kind: snapshot.kind,
+            state,
+            hitpoints: snapshot.hitpoints,
+            spawn_time: resume::ago(snapshot.age, now),
+            dropped_from: snapshot.dropped_from.map(|[x, y]| egui::pos2(x, y)),
+        }
+    }
+
+    fn is_hittable(&self) -> bool {
+        matches!(
+            self.state,
+            SupplyBoxState::Active | SupplyBoxState::Expiring(_) | SupplyBoxState::Damaged(_)
+        )
+    }
+
+    fn advance(&mut self, now: Instant, lifetime: Duration) -> bool {
+        let expires_at = self.spawn_time + lifetime;
+        match self.state {
+            SupplyBoxState::Active | SupplyBoxState::Damaged(_) if now >= expires_at => false,
+            SupplyBoxState::Active if now + EXPIRY_WARNING >= expires_at => {
+                self.state = SupplyBoxState::Expiring(now);
+                true
+            }
+            SupplyBoxState::Damaged(t) if now.duration_since(t) >= Duration::from_millis(150) => {
+                self.state = SupplyBoxState::Active;
+                true
+            }
+            SupplyBoxState::Expiring(_) => now < expires_at,
+            SupplyBoxState::Opened(t) | SupplyBoxState::Exploding(t)
+                if now.duration_since(t) >= Duration::from_millis(250) =>
+            {
+                self.state = SupplyBoxState::Destroyed(now);
+                true
+            }
+            SupplyBoxState::Destroyed(t) => now.duration_since(t) < DEBRIS_FADE,
+            _ => true,
+        }
+    }
+
+    fn sprite(&self, now: Instant) -> &'static str {
+        match self.state {
+            SupplyBoxState::Active | SupplyBoxState::Expiring(_) => self.kind.spec().sprite,
+            SupplyBoxState::Damaged(_) | SupplyBoxState::Opened(_) => {
+                "assets/sprite/supplybox_damaged.png"
+            }
+            SupplyBoxState::Primed { detonate_at, .. } => {
+                if detonate_at.saturating_duration_since(now).as_millis() / 60 % 2 == 0 {
+                    "assets/sprite/supplybox_explosion.png"
+                } else {
+                    "assets/sprite/supplybox_tnt.png"
+                }
+            }
+            SupplyBoxState::Exploding(_) => "assets/sprite/supplybox_explosion.png",
+            SupplyBoxState::Destroyed(_) => "assets/sprite/supplybox_destroyed.png",
+        }
+    }
+
+    fn tint(&self, now: Instant) -> egui::Color32 {
+        match self.state {
+            SupplyBoxState::Expiring(t) if now.duration_since(t).as_millis() / 100 % 2 == 1 => {
+                egui::Color32::from_white_alpha(60)
+            }
+            SupplyBoxState::Destroyed(t) => {
+                let fade = 1.0 - now.duration_since(t).as_secs_f32() / DEBRIS_FADE.as_secs_f32();
+                egui::Color32::from_white_alpha((fade.clamp(0.0, 1.0) * 255.0) as u8)
+            }
+            _ => egui::Color32::WHITE,
+        }
+    }
+
+    fn center(&self) -> egui::Pos2 {
+        egui::pos2(self.x + 30.0, self.y + 30.0)
+    }
+
+    fn draw_rect(&self, now: Instant, size: egui::Vec2) -> egui::Rect {
+        let target = egui::Rect::from_min_size(egui::pos2(self.x, self.y), size);
+        let Some(origin) = self.dropped_from else {
+            return target;
+        };
+        let t = now.duration_since(self.spawn_time).as_secs_f32() / DROP_ANIMATION.as_secs_f32();
+        if t >= 1.0 {
+            return target;
+        }
+        let lift = (t * std::f32::consts::PI).sin() * 40.0;
+        let center = origin.lerp(target.center(), t) - egui::vec2(0.0, lift);
+        egui::Rect::from_center_size(center, size * (0.3 + 0.7 * t))
+    }
+}
+
+impl EnemyType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyType::Normal => "Düşman",
+            EnemyType::Elite => "Elit",
+            EnemyType::Civilian => "Sivil",
+        }
+    }
+
+    fn loot(&self) -> Option<(f64, LootTable)> {
+        match self {
+            EnemyType::Normal => Some((0.08, COMMON_LOOT)),
+            EnemyType::Elite => Some((1.0, ELITE_LOOT)),
+            EnemyType::Civilian => None,
+        }
+    }
+
+    fn roll_loot(&self, rng: &mut impl Rng) -> Option<SupplyBoxType> {
+        let (chance, table) = self.loot()?;
+        rng.gen_bool(chance)
+            .then(|| SupplyBoxType::roll_from(table, rng))
+    }
 }
 
 impl Enemy {
-    pub fn new_elite() -> Self {
-        let now = Instant::now();
-        let mut rng = rand::thread_rng();
-        let x = rng.gen_range(0.0..750.0);
-        let y = rng.gen_range(0.0..500.0);
-        let texture_key = format!("assets/sprite/elite-{}.png", rng.gen_range(1..=2));
+    pub fn new(
+        enemy_type: EnemyType,
+        x: f32,
+        y: f32,
+        path: MovePath,
+        now: Instant,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let (prefix, hitpoints) = match enemy_type {
+            EnemyType::Normal => ("enemy", 1),
+            EnemyType::Elite => ("elite", 3),
+            EnemyType::Civilian => ("civilian", 1),
+        };
+        let texture_key = format!("assets/sprite/{}-{}.png", prefix, rng.gen_range(1..=2));
         let next_fire = now + Duration::from_secs_f32(1.0);
 
         Enemy {
             x,
             y,
+            origin: egui::pos2(x, y),
+            path,
             texture_key,
             state: EnemyState::Alive {
                 next_fire,
                 last_fired: None,
             },
-            enemy_type: EnemyType::Elite,
-            hitpoints: 3,
+            enemy_type,
+            hitpoints,
             spawn_time: now,
         }
     }
 
-    pub fn take_hit(&mut self) -> bool {
+    pub fn new_elite(now: Instant, rng: &mut impl Rng) -> Self {
+        let x = rng.gen_range(0.0..750.0);
+        let y = rng.gen_range(0.0..500.0);
+        Enemy::new(EnemyType::Elite, x, y, MovePath::Static, now, rng)
+    }
+
+    fn center(&self) -> egui::Pos2 {
+        egui::pos2(self.x + 37.5, self.y + 58.9)
+    }
+
+    fn snapshot(&self, now: Instant) -> EnemySnapshot {
+        let state = match &self.state {
+            EnemyState::Alive {
+                next_fire,
+                last_fired,
+            } => EnemyStateSnapshot::Alive {
+                fire_in: resume::remaining(*next_fire, now),
+                fired_ago: last_fired.map(|t| resume::elapsed(t, now)),
+            },
+            EnemyState::Firing {
+                fire_start,
+                texture_key,
+            } => EnemyStateSnapshot::Firing {
+                fired_ago: resume::elapsed(*fire_start, now),
+                texture_key: texture_key.clone(),
+            },
+            EnemyState::Dying(t0) => EnemyStateSnapshot::Dying(resume::elapsed(*t0, now)),
+        };
+        EnemySnapshot {
+            x: self.x,
+            y: self.y,
+            origin: [self.origin.x, self.origin.y],
+            path: self.path.clone(),
+            texture_key: self.texture_key.clone(),
+            state,
+            enemy_type: self.enemy_type,
+            hitpoints: self.hitpoints,
+            age: resume::elapsed(self.spawn_time, now),
+        }
+    }
+
+    fn restore(snapshot: EnemySnapshot, now: Instant) -> Self {
+        let state = match snapshot.state {
+            EnemyStateSnapshot::Alive { fire_in, fired_ago } => EnemyState::Alive {
+                next_fire: resume::after(fire_in, now),
+                last_fired: fired_ago.map(|secs| resume::ago(secs, now)),
+            },
+            EnemyStateSnapshot::Firing {
+                fired_ago,
+                texture_key,
+            } => EnemyState::Firing {
+                fire_start: resume::ago(fired_ago, now),
+                texture_key,
+            },
+            EnemyStateSnapshot::Dying(secs) => EnemyState::Dying(resume::ago(secs, now)),
+        };
+        Enemy {
+            x: snapshot.x,
+            y: snapshot.y,
+            origin: egui::pos2(snapshot.origin[0], snapshot.origin[1]),
+            path: snapshot.path,
+            texture_key: snapshot.texture_key,
+            state,
+            enemy_type: snapshot.enemy_type,
+            hitpoints: snapshot.hitpoints,
+            spawn_time: resume::ago(snapshot.age, now),
+        }
+    }
+
+    pub fn take_hit(&mut self, now: Instant) -> bool {
         if self.hitpoints > 1 {
             self.hitpoints -= 1;
             false
         } else {
-            self.state = EnemyState::Dying(Instant::now());
+            self.state = EnemyState::Dying(now);
             true
         }
     }
 }
 
 impl AdvancedApp {
-    pub fn new() -> Self {
-        let (stream, stream_handle) = OutputStream::try_default().unwrap();
-        let spawn_delay = Duration::from_secs_f32(rand::thread_rng().gen_range(0.5..=1.0));
-        Self {
+    pub fn new(settings: Settings) -> Self {
+        let mut app = Self::seeded(settings, rand::random());
+        app.attach_ghost();
+        app
+    }
+
+    pub fn seeded(settings: Settings, seed: u64) -> Self {
+        Self::with_sounds(settings, seed, SoundBoard::new())
+    }
+
+    fn with_sounds(settings: Settings, seed: u64, sounds: SoundBoard) -> Self {
+        let now = Instant::now();
+        let mut app = Self {
             score: 0,
+            score_fraction: 0.0,
             enemies: Vec::new(),
             supply_boxes: Vec::new(),
-            next_enemy_spawn_time: Instant::now() + spawn_delay,
-            next_elite_spawn_time: Instant::now() + Duration::from_secs(30),
-            next_supply_time: Instant::now()
-                + Duration::from_secs_f32(rand::thread_rng().gen_range(5.0..=8.0)),
+            next_enemy_spawn_time: now,
+            next_elite_spawn_time: now + Duration::from_secs(30),
+            next_supply_time: now,
             textures: HashMap::new(),
             game_time: Duration::ZERO,
-            last_update: Instant::now(),
+            last_update: now,
             visible_time: 30,
-            start_time: Instant::now(),
+            start_time: now,
             game_over: false,
             show_intro: true,
-            intro_start: Instant::now(),
+            intro_start: now,
             restart_count: 0,
-            audio_stream: stream,
-            audio_handle: stream_handle,
-        }
+            friendly_fire: 0,
+            boss: None,
+            boss_spawned: false,
+            boss_defeated_at: None,
+            script: None,
+            level_complete: false,
+            result_taken: false,
+            summary_taken: false,
+            tally: RoundTally::default(),
+            achievements: AchievementTracker::default(),
+            events: EventBus::default(),
+            juice: Juice::new(settings.reduced_motion, now),
+            streak: 0,
+            best_streak: 0,
+            upgrades: UpgradeStack::default(),
+            params: SimParams::default(),
+            coins: 0,
+            wave: 1,
+            wave_end: WAVE_LENGTH,
+            shopping: false,
+            ammo: SimParams::default().magazine_size,
+            reload_until: None,
+            shockwaves: Vec::new(),
+            chain_banner: None,
+            sim_now: now,
+            effects: Vec::new(),
+            settings,
+            difficulty: settings.difficulty.params(),
+            director: settings.director.then(Director::new),
+            survival: None,
+            survival_rank: None,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            mutators: settings.mutators,
+            daily: None,
+            missed_one_shot: false,
+            seed,
+            pointer: None,
+            pending_action: None,
+            recording: Some(Vec::new()),
+            replaying: false,
+            ghost: None,
+            sounds,
+        };
+        app.reseed(seed);
+        app
     }
 
-    pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
-        let now = Instant::now();
+    fn reseed(&mut self, seed: u64) {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let now = self.last_update;
+        self.next_enemy_spawn_time = now + self.difficulty.spawn_delay(1.0, &mut rng);
+        self.next_supply_time = now + Duration::from_secs_f32(rng.gen_range(5.0..=8.0));
+        self.rng = rng;
+        self.seed = seed;
+    }
 
-        let delta = now.duration_since(self.last_update);
-        if !self.show_intro && !self.game_over {
-            self.game_time += delta;
-        }
-        self.last_update = now;
+    pub fn with_level(level: Level, settings: Settings) -> Self {
+        let mut app = Self::seeded(settings, rand::random());
+        app.load_level(level);
+        app.attach_ghost();
+        app
+    }
 
-        let mut signal = None;
-        let mut fire_events: Vec<i64> = Vec::new();
+    pub fn from_replay(replay: &Replay) -> Result<Self, String> {
+        let mut app = Self::seeded(replay.settings, replay.seed);
+        match &replay.kind {
+            ReplayKind::Advanced | ReplayKind::Daily => {}
+            ReplayKind::Survival => app.survival = Some(SurvivalBoard::default()),
+            ReplayKind::Level(source) => app.load_level(Level::parse(source)?),
+            ReplayKind::Classic => return Err("Klasik tur tekrarı değil".to_string()),
+        }
+        app.recording = None;
+        app.replaying = true;
+        app.juice = Juice::new(Settings::load().reduced_motion, app.sim_now);
+        Ok(app)
+    }
 
-        for name in [
-            "assets/sprite/enemy-1.png",
-            "assets/sprite/enemy-2.png",
-            "assets/sprite/enemy_death-1.png",
-            "assets/sprite/enemy_death-2.png",
-            "assets/sprite/enemy_fire.png",
-            "assets/sprite/supplybox_health.png",
-            "assets/sprite/supplybox_tnt.png",
-            "assets/sprite/supplybox_damaged.png",
-            "assets/sprite/supplybox_explosion.png",
-            "assets/sprite/supplybox_destroyed.png",
-            "assets/sprite/elite-1.png",
-            "assets/sprite/elite-2.png",
-            "assets/sprite/elite_fire.png",
-            "assets/sprite/elite_death-1.png",
-            "assets/sprite/elite_death-2.png",
-        ] {
-            if !self.textures.contains_key(name) {
-                let texture = load_image(ctx, name);
-                self.textures.insert(name.to_string(), texture);
+    pub fn resume(snapshot: AdvancedSnapshot) -> Result<Self, String> {
+        let mut app = Self::seeded(snapshot.settings, u64::from_le_bytes(snapshot.seed));
+        let now = app.sim_now;
+        match snapshot.kind {
+            RoundKind::Advanced => {}
+            RoundKind::Survival => app.survival = Some(SurvivalBoard::load()),
+            RoundKind::Level { source, cursor } => {
+                app.script = Some(LevelScript::resume(Level::parse(&source)?, cursor));
+            }
+            RoundKind::Daily { date, official } => {
+                app.daily = Some(DailyRun::resume(&date, official));
             }
         }
+        let mut rng = ChaCha12Rng::from_seed(snapshot.rng_seed);
+        rng.set_word_pos(snapshot.rng_word_pos as u128);
+        app.rng = rng;
+        app.score = snapshot.score;
+        app.score_fraction = snapshot.score_fraction;
+        app.visible_time = snapshot.visible_time;
+        app.game_time = Duration::from_secs_f64(snapshot.game_time);
+        app.start_time = resume::ago(snapshot.started_ago, now);
+        app.show_intro = snapshot.intro_ago.is_some();
+        app.intro_start = resume::ago(snapshot.intro_ago.unwrap_or_default(), now);
+        app.next_enemy_spawn_time = resume::after(snapshot.next_enemy_in, now);
+        app.next_elite_spawn_time = resume::after(snapshot.next_elite_in, now);
+        app.next_supply_time = resume::after(snapshot.next_supply_in, now);
+        app.friendly_fire = snapshot.friendly_fire;
+        app.boss_spawned = snapshot.boss_spawned;
+        app.streak = snapshot.streak;
+        app.best_streak = snapshot.best_streak;
+        app.coins = snapshot.coins;
+        app.wave = snapshot.wave;
+        app.wave_end = Duration::from_secs_f64(snapshot.wave_end);
+        app.shopping = snapshot.shopping;
+        app.ammo = snapshot.ammo;
+        app.reload_until = snapshot.reload_in.map(|secs| resume::after(secs, now));
+        app.upgrades = snapshot.upgrades;
+        app.params = app.upgrades.params();
+        app.director = snapshot.director;
+        app.tally = snapshot.tally;
+        app.boss = snapshot.boss.map(|boss| Boss::restore(boss, now));
+        app.effects = snapshot
+            .effects
+            .into_iter()
+            .map(|effect| ActiveEffect {
+                kind: effect.kind,
+                until: resume::after(effect.remaining, now),
+            })
+            .collect();
+        app.enemies = snapshot
+            .enemies
+            .into_iter()
+            .map(|enemy| Enemy::restore(enemy, now))
+            .collect();
+        app.supply_boxes = snapshot
+            .supply_boxes
+            .into_iter()
+            .map(|supply| SupplyBox::restore(supply, now))
+            .collect();
+        app.recording = snapshot
+            .recording
+            .and_then(|frames| replay::unpack_frames(&frames));
+        Ok(app)
+    }
 
-        let enemy_display_width = 75.0;
-        let box_display_width = 60.0;
-        let enemy_size = enemy_display_width * (55.0 / 35.0);
-        let box_size = box_display_width * (35.0 / 35.0);
+    pub fn snapshot(&self) -> Option<AdvancedSnapshot> {
+        if self.game_over || self.replaying {
+            return None;
+        }
+        let now = self.sim_now;
+        let real_now = self.last_update;
+        let kind = match (&self.script, &self.survival, &self.daily) {
+            (Some(script), _, _) => RoundKind::Level {
+                source: script.level.source.clone(),
+                cursor: script.cursor(),
+            },
+            (None, Some(_), _) => RoundKind::Survival,
+            (None, None, Some(run)) => RoundKind::Daily {
+                date: run.challenge.date.clone(),
+                official: run.official,
+            },
+            (None, None, None) => RoundKind::Advanced,
+        };
+        Some(AdvancedSnapshot {
+            kind,
+            settings: self.settings,
+            rng_seed: self.rng.get_seed(),
+            rng_word_pos: self.rng.get_word_pos() as u64,
+            seed: self.seed.to_le_bytes(),
+            recording: self.recording.as_deref().map(replay::pack_frames),
+            score: self.score,
+            score_fraction: self.score_fraction,
+            visible_time: self.visible_time,
+            game_time: self.game_time.as_secs_f64(),
+            started_ago: resume::elapsed(self.start_time, now),
+            intro_ago: self
+                .show_intro
+                .then(|| resume::elapsed(self.intro_start, now)),
+            next_enemy_in: resume::remaining(self.next_enemy_spawn_time, now),
+            next_elite_in: resume::remaining(self.next_elite_spawn_time, now),
+            next_supply_in: resume::remaining(self.next_supply_time, now),
+            friendly_fire: self.friendly_fire,
+            boss_spawned: self.boss_spawned,
+            streak: self.streak,
+            best_streak: self.best_streak,
+            coins: self.coins,
+            wave: self.wave,
+            wave_end: self.wave_end.as_secs_f64(),
+            shopping: self.shopping,
+            ammo: self.ammo,
+            reload_in: self
+                .reload_until
+                .map(|until| resume::remaining(until, real_now)),
+            upgrades: self.upgrades.clone(),
+            director: self.director.clone(),
+            tally: self.tally.clone(),
+            boss: self.boss.as_ref().map(|boss| boss.snapshot(now)),
+            effects: self
+                .effects
+                .iter()
+                .map(|effect| EffectSnapshot {
+                    kind: effect.kind,
+                    remaining: resume::remaining(effect.until, real_now),
+                })
+                .collect(),
+            enemies: self
+                .enemies
+                .iter()
+                .map(|enemy| enemy.snapshot(now))
+                .collect(),
+            supply_boxes: self
+                .supply_boxes
+                .iter()
+                .map(|supply| supply.snapshot(now))
+                .collect(),
+        })
+    }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let elapsed_intro = now.duration_since(self.intro_start);
+    fn load_level(&mut self, level: Level) {
+        self.visible_time = level.start_time;
+        self.script = Some(LevelScript::new(level));
+    }
 
-            if self.show_intro {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(200.0);
-                    if self.restart_count == 0 {
-                        if elapsed_intro < Duration::from_secs(2) {
-                            ui.heading(RichText::new("Hazır mısın?").size(36.0));
-                        } else if elapsed_intro < Duration::from_secs(4) {
-                            ui.heading(RichText::new("Başla!").size(36.0));
-                        } else {
-                            self.start_time = now;
-                            self.show_intro = false;
-                        }
-                    } else {
-                        if elapsed_intro < Duration::from_secs(2) {
-                            ui.heading(RichText::new("Hazır mısın?").size(36.0));
-                        } else if elapsed_intro < Duration::from_secs(4) {
-                            ui.heading(RichText::new("Başla!").size(36.0));
-                        } else {
-                            self.start_time = now;
-                            self.show_intro = false;
-                        }
-                    }
-                });
-                return;
-            }
+    pub fn survival(settings: Settings) -> Self {
+        let mut app = Self::seeded(settings, rand::random());
+        app.survival = Some(SurvivalBoard::load());
+        app.attach_ghost();
+        app
+    }
 
-            if self.visible_time <= 0 {
-                self.game_over = true;
-            }
+    pub fn daily(settings: Settings) -> Self {
+        let mut run = DailyRun::start();
+        if run.official {
+            run.history.begin(&run.challenge);
+        }
+        let settings = Settings {
+            mutators: run.challenge.mutators,
+            ghost: settings.ghost,
+            reduced_motion: settings.reduced_motion,
+            ..Settings::default()
+        };
+        let mut app = Self::seeded(settings, run.challenge.seed);
+        app.daily = Some(run);
+        app.attach_ghost();
+        app
+    }
 
-            if self.game_over {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(150.0);
-                    ui.heading(RichText::new("Gelişmiş - Oyun Bitti!").size(32.0));
-                    ui.add_space(20.0);
-                    ui.label(RichText::new(format!("Toplam Puan: {}", self.score)).size(24.0));
-                    ui.add_space(20.0);
+    fn replay_kind(&self) -> ReplayKind {
+        match (&self.script, &self.survival, &self.daily) {
+            (Some(script), _, _) => ReplayKind::Level(script.level.source.clone()),
+            (None, Some(_), _) => ReplayKind::Survival,
+            (None, None, Some(_)) => ReplayKind::Daily,
+            (None, None, None) => ReplayKind::Advanced,
+        }
+    }
 
-                    if ui.button(RichText::new("Tekrar Oyna").size(20.0)).clicked() {
-                        signal = Some("restart".to_string());
-                    }
+    fn attach_ghost(&mut self) {
+        if !self.settings.ghost {
+            return;
+        }
+        let kind = self.replay_kind();
+        let Some(best) = Replay::best(&kind, self.settings, self.seed) else {
+            return;
+        };
+        let Ok(mut app) = AdvancedApp::from_replay(&best) else {
+            return;
+        };
+        app.set_muted(true);
+        if !matches!(kind, ReplayKind::Daily) {
+            self.reseed(best.seed);
+        }
+        self.ghost = Some(Box::new(Ghost {
+            app,
+            frames: best.frames,
+            index: 0,
+            clock: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            best_score: best.score,
+        }));
+    }
 
-                    ui.add_space(10.0);
+    pub fn restart(&self) -> Self {
+        match &self.script {
+            Some(script) => Self::with_level(script.level.clone(), self.settings),
+            None if self.survival.is_some() => Self::survival(self.settings),
+            None if self.daily.is_some() => Self::daily(self.settings),
+            None => Self::new(self.settings),
+        }
+    }
 
-                    if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
-                        signal = Some("menu".to_string());
-                    }
-                });
-                return;
-            }
+    pub fn take_result(&mut self) -> Option<u8> {
+        if !self.game_over || self.result_taken {
+            return None;
+        }
+        self.result_taken = true;
+        self.stars()
+    }
 
-            ui.label(RichText::new(format!("Süre: {}", self.visible_time)).size(20.0));
-            ui.label(RichText::new(format!("Skor: {}", self.score)).size(20.0));
+    pub fn take_summary(&mut self) -> Option<RoundSummary> {
+        if !self.game_over || self.summary_taken || self.replaying {
+            return None;
+        }
+        self.summary_taken = true;
+        let mode = match (&self.script, &self.survival, &self.daily) {
+            (Some(script), _, _) => script.level.name.clone(),
+            (None, Some(_), _) => "Hayatta Kalma".to_string(),
+            (None, None, Some(_)) => "Günlük Görev".to_string(),
+            (None, None, None) => "Gelişmiş".to_string(),
+        };
+        Some(RoundSummary {
+            mode,
+            difficulty: self.settings.difficulty,
+            score: self.score,
+            secs: self.game_time.as_secs_f32(),
+            timed: false,
+            tally: self.tally.clone(),
+        })
+    }
 
-            if now >= self.next_enemy_spawn_time {
-                self.spawn_enemy();
-                let spawn_delay = Duration::from_secs_f32(rand::thread_rng().gen_range(0.5..=1.0));
-                self.next_enemy_spawn_time = now + spawn_delay;
-            }
+    pub fn take_achievements(&mut self) -> Vec<Achievement> {
+        self.achievements.take()
+    }
 
-            if now >= self.next_elite_spawn_time && self.game_time >= Duration::from_secs(30) {
-                self.spawn_elite();
-            }
+    fn stars(&self) -> Option<u8> {
+        let objective = self.script.as_ref()?.level.objective.as_ref()?;
+        Some(objective.stars(&RoundStats {
+            score: self.score,
+            survived_secs: self.game_time.as_secs() as u32,
+            best_streak: self.best_streak,
+            completed: self.level_complete,
+        }))
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
+        if !self.game_over && !self.replaying && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            return Some("menu".to_string());
+        }
+        let elapsed = Instant::now().duration_since(self.last_update);
+        let input = ctx.input(|i| FrameInput {
+            dt: Duration::from_micros(elapsed.as_micros().min(u32::MAX as u128) as u64),
+            pointer: i.pointer.interact_pos(),
+            click: i.pointer.primary_clicked(),
+            reload: i.key_pressed(egui::Key::R),
+            action: self.pending_action.take(),
+        });
+        self.step(&input, ctx.screen_rect());
+        self.draw(ctx)
+    }
 
-            let total_elapsed = now.duration_since(self.start_time);
+    pub fn step(&mut self, input: &FrameInput, field: egui::Rect) -> Vec<GameEvent> {
+        let real_now = self.last_update + input.dt;
+        self.effects.retain(|effect| real_now < effect.until);
 
-            if now >= self.next_supply_time && total_elapsed >= Duration::from_secs(10) {
-                let mut rng = rand::thread_rng();
-                let kind = if rng.gen_bool(0.65) {
-                    SupplyBoxType::Health
-                } else {
-                    SupplyBoxType::Tnt
-                };
-                let box_x = rng.gen_range(0.0..700.0);
-                let box_y = rng.gen_range(0.0..450.0);
-                self.supply_boxes.push(SupplyBox {
-                    x: box_x,
-                    y: box_y,
-                    kind,
-                    state: SupplyBoxState::Active,
-                    spawn_time: now,
-                });
-                self.next_supply_time = now + Duration::from_secs_f32(rng.gen_range(5.0..=8.0));
+        let mut delta = input.dt;
+        if self.effect_active(SupplyBoxType::SlowMotion) {
+            delta = delta.mul_f32(SLOW_MOTION_SPEED);
+        }
+        if self.mutators.contains(Mutator::DoubleSpeed) {
+            delta = delta.mul_f32(DOUBLE_SPEED);
+        }
+        let was_shopping = self.shopping;
+        let was_over = self.game_over;
+        if !self.show_intro && !self.game_over && !self.shopping {
+            self.game_time += delta;
+            if self.game_time >= achievement::SURVIVOR_TIME && !self.replaying {
+                self.achievements.achieve(Achievement::Survivor);
             }
+        }
+        self.last_update = real_now;
+        self.sim_now += delta;
+        self.pointer = input.pointer.map(|pos| self.mutators.aim(pos, field));
+        if let Some(frames) = &mut self.recording {
+            frames.push(*input);
+        }
 
-            self.enemies.retain_mut(|enemy| {
-                match &mut enemy.state {
-                    EnemyState::Alive { next_fire, .. } => {
-                        if now >= *next_fire {
-                            let damage = match enemy.enemy_type {
-                                EnemyType::Normal => 1,
-                                EnemyType::Elite => 3,
-                            };
-                            enemy.state = EnemyState::Firing {
-                                fire_start: now,
-                                texture_key: enemy.texture_key.clone(),
-                            };
-                            fire_events.push(damage);
-                        }
-                        true
-                    }
-                    EnemyState::Firing { fire_start, .. } => {
-                        if now.duration_since(*fire_start) >= Duration::from_millis(500) {
-                            let mut rng = rand::thread_rng();
-                            let delay = rng.gen_range(0.7..=1.2);
-                            enemy.state = EnemyState::Alive {
-                                next_fire: now + Duration::from_secs_f32(delay),
-                                last_fired: Some(*fire_start),
-                            };
-                        }
-                        true
-                    }
-                    EnemyState::Dying(t0) => now.duration_since(*t0) < Duration::from_millis(500),
-                }
-            });
+        let time_before = self.visible_time;
+        self.simulate(input, real_now, delta);
 
-            self.supply_boxes.retain(|supply| match &supply.state {
-                SupplyBoxState::Active => {
-                    now.duration_since(supply.spawn_time) < Duration::from_secs(3)
-                }
-                SupplyBoxState::Destroyed(t)
-                | SupplyBoxState::Damaged(t)
-                | SupplyBoxState::Exploding(t) => {
-                    now.duration_since(*t) < Duration::from_millis(500)
-                }
+        if !self.show_intro && !self.game_over && !was_shopping {
+            self.handle_input(input, real_now);
+        }
+        if self.visible_time < time_before {
+            self.events.emit(GameEvent::TimeLost {
+                seconds: time_before - self.visible_time,
             });
+        }
 
-            for damage in fire_events {
-                let sound = if damage == 3 {
-                    "assets/sound/elite_fire.wav"
-                } else {
-                    "assets/sound/enemy_fire.mp3"
-                };
+        let elapsed_secs = self.game_time.as_secs();
+        let prev_elapsed_secs = self.game_time.saturating_sub(delta).as_secs();
+
+        if elapsed_secs > prev_elapsed_secs {
+            self.visible_time -= 1;
+            self.visible_time = self.visible_time.clamp(0, self.params.max_time);
+        }
 
-                self.play_sound(sound);
-                self.visible_time -= damage;
-                self.visible_time = self.visible_time.clamp(0, 60);
+        if let Some(ghost) = &mut self.ghost {
+            ghost.elapsed += input.dt;
+            while let Some(frame) = ghost.frames.get(ghost.index)
+                && ghost.clock + frame.dt <= ghost.elapsed
+            {
+                ghost.app.step(frame, field);
+                ghost.clock += frame.dt;
+                ghost.index += 1;
             }
+        }
 
-            for enemy in &self.enemies {
-                let tex_key = match (&enemy.enemy_type, &enemy.state) {
-                    (EnemyType::Normal, EnemyState::Alive { .. }) => enemy.texture_key.clone(),
-                    (EnemyType::Normal, EnemyState::Firing { .. }) => {
+        if !was_over && self.game_over {
+            self.events
+                .emit(GameEvent::RoundEnded { score: self.score });
+            self.save_replay();
+        }
+
+        let events = self.events.drain();
+        self.juice.advance(self.sim_now);
+        for event in &events {
+            self.tally.on_event(event);
+            self.sounds.on_event(event);
+            self.juice.on_event(event);
+            if !self.replaying {
+                self.achievements.on_event(event);
+            }
+            self.show_event(event);
+        }
+        events
+    }
+
+    fn show_event(&mut self, event: &GameEvent) {
+        if let GameEvent::TntExploded {
+            center,
+            radius,
+            chain,
+            ..
+        } = *event
+        {
+            self.shockwaves.push(Shockwave {
+                center,
+                radius,
+                start: self.sim_now,
+            });
+            if chain >= 2 {
+                self.chain_banner = Some((self.sim_now, chain));
+            }
+        }
+    }
+
+    fn simulate(&mut self, input: &FrameInput, real_now: Instant, delta: Duration) {
+        let now = self.sim_now;
+        let mut fire_events: Vec<(i64, EnemyType)> = Vec::new();
+
+        if self.show_intro {
+            if now.duration_since(self.intro_start) >= Duration::from_secs(4) {
+                self.start_time = now;
+                self.show_intro = false;
+                self.events.emit(GameEvent::RoundStarted);
+            }
+            return;
+        }
+        if self.visible_time <= 0 && !self.game_over {
+            self.game_over = true;
+            if let Some(board) = &mut self.survival
+                && !self.replaying
+            {
+                self.survival_rank = board.record(SurvivalEntry {
+                    secs: self.game_time.as_secs_f32(),
+                    score: self.score,
+                    difficulty: self.settings.difficulty,
+                });
+            }
+            if let Some(run) = &mut self.daily
+                && run.official
+            {
+                run.history.record(DailyEntry {
+                    date: run.challenge.date.clone(),
+                    score: self.score,
+                    secs: self.game_time.as_secs_f32(),
+                    mutators: self.mutators,
+                    in_progress: false,
+                });
+            }
+        }
+
+        if self.game_over {
+            return;
+        }
+
+        if self.shopping {
+            match input.action {
+                Some(ShopAction::Buy(index)) => {
+                    if let Some(perk) = ALL_PERKS.get(index) {
+                        self.buy_perk(*perk);
+                    }
+                }
+                Some(ShopAction::NextWave) => self.start_next_wave(now),
+                None => {}
+            }
+            return;
+        }
+
+        if let Some(until) = self.reload_until
+            && real_now >= until
+        {
+            self.ammo = self.params.magazine_size;
+            self.reload_until = None;
+        }
+
+        if let Some(director) = &mut self.director {
+            director.update(
+                delta,
+                self.visible_time as f32 / self.params.max_time as f32,
+            );
+        }
+
+        if self.script.is_some() {
+            self.run_script();
+        } else {
+            if self.survival.is_none()
+                && self.game_time >= self.wave_end
+                && !self.boss.as_ref().is_some_and(|boss| boss.is_alive())
+            {
+                self.shopping = true;
+                self.enemies.clear();
+                self.supply_boxes.clear();
+                self.effects.clear();
+                return;
+            }
+            self.run_default_spawns(now);
+        }
+
+        let elite_damage = self.params.elite_damage;
+        let supply_lifetime = self.params.supply_lifetime;
+        let frozen = self.effect_active(SupplyBoxType::Freeze);
+        let difficulty = self.difficulty;
+        let pace = self.pace();
+        let rng = &mut self.rng;
+        let mutators = self.mutators;
+
+        self.enemies.retain_mut(|enemy| {
+            if !matches!(enemy.state, EnemyState::Dying(_)) {
+                let t = now.duration_since(enemy.spawn_time).as_secs_f32();
+                let pos = enemy
+                    .path
+                    .position(enemy.origin, t, egui::pos2(750.0, 500.0))
+                    + mutators.float_offset(t, enemy.origin.x);
+                enemy.x = pos.x;
+                enemy.y = pos.y;
+            }
+
+            match &mut enemy.state {
+                EnemyState::Alive { .. } if matches!(enemy.enemy_type, EnemyType::Civilian) => {
+                    now.duration_since(enemy.spawn_time) < Duration::from_secs(3)
+                }
+                EnemyState::Alive { next_fire, .. } => {
+                    if frozen {
+                        if now >= *next_fire {
+                            *next_fire = now + difficulty.fire_delay(pace, rng);
+                        }
+                    } else if now >= *next_fire {
+                        let damage = match enemy.enemy_type {
+                            EnemyType::Normal | EnemyType::Civilian => 1,
+                            EnemyType::Elite => elite_damage,
+                        };
+                        enemy.state = EnemyState::Firing {
+                            fire_start: now,
+                            texture_key: enemy.texture_key.clone(),
+                        };
+                        fire_events.push((damage, enemy.enemy_type));
+                    }
+                    true
+                }
+                EnemyState::Firing { fire_start, .. } => {
+                    if now.duration_since(*fire_start) >= Duration::from_millis(500) {
+                        enemy.state = EnemyState::Alive {
+                            next_fire: now + difficulty.fire_delay(pace, rng),
+                            last_fired: Some(*fire_start),
+                        };
+                    }
+                    true
+                }
+                EnemyState::Dying(t0) => now.duration_since(*t0) < Duration::from_millis(500),
+            }
+        });
+
+        if let Some(boss) = &mut self.boss
+            && !frozen
+        {
+            let shots = boss.update(now, delta, &mut self.rng) as usize;
+            fire_events.extend(std::iter::repeat_n((1, EnemyType::Elite), shots));
+            if boss.is_gone(now) {
+                self.boss = None;
+            }
+        }
+
+        let survive_goal = self
+            .script
+            .as_ref()
+            .and_then(|script| script.level.objective.as_ref())
+            .and_then(|objective| objective.survive_goal());
+        let cleared = match survive_goal {
+            Some(goal) => self.game_time.as_secs() >= goal as u64,
+            None => {
+                self.script
+                    .as_ref()
+                    .is_some_and(|script| script.is_finished())
+                    && self.boss.is_none()
+                    && !self.enemies.iter().any(|enemy| {
+                        !matches!(enemy.enemy_type, EnemyType::Civilian)
+                            && !matches!(enemy.state, EnemyState::Dying(_))
+                    })
+            }
+        };
+        if cleared {
+            self.level_complete = true;
+            self.game_over = true;
+        }
+
+        let primed: Vec<(usize, u32)> = self
+            .supply_boxes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, supply)| match supply.state {
+                SupplyBoxState::Primed { detonate_at, chain } if now >= detonate_at => {
+                    Some((index, chain))
+                }
+                _ => None,
+            })
+            .collect();
+        for (index, chain) in primed {
+            self.detonate_tnt(index, chain);
+        }
+
+        self.shockwaves
+            .retain(|wave| now.duration_since(wave.start) < Duration::from_millis(400));
+
+        self.supply_boxes
+            .retain_mut(|supply| supply.advance(now, supply_lifetime));
+
+        let shielded = self.effect_active(SupplyBoxType::Shield);
+        for (damage, enemy_type) in fire_events {
+            self.events.emit(GameEvent::EnemyFired {
+                enemy_type,
+                damage,
+                blocked: shielded,
+            });
+            if shielded {
+                continue;
+            }
+            self.visible_time -= damage;
+            self.visible_time = self.visible_time.clamp(0, self.params.max_time);
+        }
+    }
+
+    fn handle_input(&mut self, input: &FrameInput, real_now: Instant) {
+        let now = self.sim_now;
+        let clicked_pos = match self.pointer {
+            Some(pos) if input.click && self.reload_until.is_none() && self.ammo > 0 => Some(pos),
+            _ => None,
+        };
+
+        if clicked_pos.is_some() {
+            self.ammo -= 1;
+        }
+
+        if self.reload_until.is_none()
+            && (self.ammo == 0 || (input.reload && self.ammo < self.params.magazine_size))
+        {
+            self.reload_until = Some(real_now + self.params.reload_time);
+        }
+
+        if let Some(pos) = clicked_pos {
+            let mut hit =
+                self.handle_click_on_enemy(pos.x, pos.y) || self.handle_click_on_boss(pos);
+
+            for (index, supply) in self.supply_boxes.iter_mut().enumerate() {
+                if supply.is_hittable() {
+                    let rect = egui::Rect::from_min_size(
+                        egui::pos2(supply.x, supply.y),
+                        egui::vec2(60.0, 60.0),
+                    );
+                    if rect.contains(pos) {
+                        hit = true;
+                        let kind = supply.kind;
+                        supply.hitpoints = supply.hitpoints.saturating_sub(1);
+                        if supply.hitpoints > 0 {
+                            supply.state = SupplyBoxState::Damaged(now);
+                            self.events.emit(GameEvent::SupplyDamaged { kind });
+                        } else if kind == SupplyBoxType::Tnt {
+                            self.events.emit(GameEvent::SupplyCollected { kind });
+                            self.visible_time -= 5;
+                            self.visible_time = self.visible_time.clamp(0, self.params.max_time);
+
+                            self.detonate_tnt(index, 1);
+                        } else {
+                            supply.state = SupplyBoxState::Opened(now);
+                            self.collect_supply(kind, real_now);
+                        }
+
+                        break;
+                    }
+                }
+            }
+
+            if !hit {
+                self.streak = 0;
+                if self.mutators.contains(Mutator::OneShot) {
+                    self.missed_one_shot = true;
+                    self.visible_time = 0;
+                }
+            }
+            self.events.emit(GameEvent::ShotFired { pos, hit });
+            if let Some(director) = &mut self.director {
+                director.record_shot(hit);
+            }
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &egui::Context) -> Option<String> {
+        let now = self.sim_now;
+        let real_now = self.last_update;
+        let mut signal = None;
+
+        for name in [
+            "assets/sprite/enemy-1.png",
+            "assets/sprite/enemy-2.png",
+            "assets/sprite/enemy_death-1.png",
+            "assets/sprite/enemy_death-2.png",
+            "assets/sprite/enemy_fire.png",
+            "assets/sprite/supplybox_damaged.png",
+            "assets/sprite/supplybox_explosion.png",
+            "assets/sprite/supplybox_destroyed.png",
+            "assets/sprite/elite-1.png",
+            "assets/sprite/elite-2.png",
+            "assets/sprite/elite_fire.png",
+            "assets/sprite/elite_death-1.png",
+            "assets/sprite/elite_death-2.png",
+            "assets/sprite/civilian-1.png",
+            "assets/sprite/civilian-2.png",
+            "assets/sprite/civilian_death-1.png",
+            "assets/sprite/civilian_death-2.png",
+        ]
+        .into_iter()
+        .chain(SupplyBoxType::sprites())
+        {
+            if !self.textures.contains_key(name) {
+                let texture = load_image(ctx, name);
+                self.textures.insert(name.to_string(), texture);
+            }
+        }
+
+        let enemy_display_width = 75.0;
+        let box_display_width = 60.0;
+        let enemy_size = enemy_display_width * (55.0 / 35.0);
+        let box_size = box_display_width * (35.0 / 35.0);
+
+        let frozen = self.effect_active(SupplyBoxType::Freeze);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.show_intro {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(200.0);
+                    let elapsed_intro = now.duration_since(self.intro_start);
+                    if self.restart_count == 0 {
+                        if elapsed_intro < Duration::from_secs(2) {
+                            ui.heading(RichText::new("Hazır mısın?").size(36.0));
+                        } else {
+                            ui.heading(RichText::new("Başla!").size(36.0));
+                        }
+                    } else {
+                        if elapsed_intro < Duration::from_secs(2) {
+                            ui.heading(RichText::new("Hazır mısın?").size(36.0));
+                        } else {
+                            ui.heading(RichText::new("Başla!").size(36.0));
+                        }
+                    }
+                    ui.add_space(20.0);
+                    ui.label(
+                        RichText::new("Esc: turu kaydet ve menüye dön")
+                            .size(16.0)
+                            .color(egui::Color32::GRAY),
+                    );
+                });
+                return;
+            }
+
+            if self.game_over {
+                ui.vertical_centered(|ui| {
+                    let boards = self.survival.is_some() || self.daily.is_some();
+                    ui.add_space(if boards { 40.0 } else { 150.0 });
+                    let title = if self.level_complete {
+                        "Senaryo Tamamlandı!"
+                    } else if self.survival.is_some() {
+                        "Hayatta Kalma - Oyun Bitti!"
+                    } else if self.daily.is_some() {
+                        "Günlük Görev - Oyun Bitti!"
+                    } else {
+                        "Gelişmiş - Oyun Bitti!"
+                    };
+                    ui.heading(RichText::new(title).size(32.0));
+                    ui.add_space(20.0);
+                    ui.label(RichText::new(format!("Toplam Puan: {}", self.score)).size(24.0));
+                    if !self.mutators.is_empty() {
+                        ui.label(
+                            RichText::new(self.mutators.summary())
+                                .size(18.0)
+                                .color(egui::Color32::from_rgb(200, 120, 0)),
+                        );
+                    }
+                    if self.missed_one_shot {
+                        ui.label(RichText::new("Tek atış kaçtı!").size(18.0));
+                    }
+                    if let Some(ghost) = &self.ghost {
+                        let text = if self.score > ghost.best_score {
+                            format!("Hayalet geçildi! Önceki rekor: {}", ghost.best_score)
+                        } else {
+                            format!("Hayalet rekoru: {}", ghost.best_score)
+                        };
+                        ui.label(RichText::new(text).size(18.0).color(egui::Color32::GOLD));
+                    }
+                    ui.label(
+                        RichText::new(format!("Sivil Kayıp: {}", self.friendly_fire))
+                            .size(20.0)
+                            .color(egui::Color32::RED),
+                    );
+                    if let Some(stars) = self.stars() {
+                        let stars = stars.min(3) as usize;
+                        let filled = "★".repeat(stars);
+                        let empty = "☆".repeat(3 - stars);
+                        ui.add_space(10.0);
+                        ui.label(
+                            RichText::new(format!("{}{}", filled, empty))
+                                .size(32.0)
+                                .color(egui::Color32::GOLD),
+                        );
+                    }
+                    if let Some(board) = &self.survival {
+                        self.draw_leaderboard(ui, board);
+                    }
+                    if let Some(run) = &self.daily {
+                        self.draw_daily_history(ui, run);
+                    }
+                    ui.add_space(20.0);
+                    if self.replaying {
+                        return;
+                    }
+
+                    if ui.button(RichText::new("Tekrar Oyna").size(20.0)).clicked() {
+                        signal = Some("restart".to_string());
+                    }
+
+                    ui.add_space(10.0);
+
+                    if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                        signal = Some("menu".to_string());
+                    }
+
+                    ui.add_space(10.0);
+
+                    if ui
+                        .button(RichText::new("Isı Haritası").size(20.0))
+                        .clicked()
+                    {
+                        signal = Some("heatmap".to_string());
+                    }
+                });
+                return;
+            }
+
+            if self.shopping {
+                self.draw_shop(ui);
+                return;
+            }
+
+            ui.label(RichText::new(format!("Süre: {}", self.visible_time)).size(20.0));
+            ui.label(RichText::new(format!("Skor: {}", self.score)).size(20.0));
+            if let Some(ghost) = &self.ghost {
+                let delta = self.score as i64 - ghost.app.score as i64;
+                let color = if delta >= 0 {
+                    egui::Color32::from_rgb(0, 160, 0)
+                } else {
+                    egui::Color32::RED
+                };
+                ui.label(
+                    RichText::new(format!("Hayalet: {} ({:+})", ghost.app.score, delta))
+                        .size(18.0)
+                        .color(color),
+                );
+            }
+            if self.reload_until.is_some() {
+                ui.label(RichText::new("Şarjör değiştiriliyor...").size(20.0));
+            } else {
+                ui.label(
+                    RichText::new(format!(
+                        "Mermi: {}/{}",
+                        self.ammo, self.params.magazine_size
+                    ))
+                    .size(20.0),
+                );
+            }
+            if self.survival.is_some() {
+                let secs = self.game_time.as_secs_f32();
+                let threat = survival::threat_level(secs);
+                ui.label(
+                    RichText::new(format!("Tehdit: {}", threat.name))
+                        .size(20.0)
+                        .color(threat.color),
+                );
+                ui.label(
+                    RichText::new(format!("Hayatta Kalma: {}", survival::format_time(secs)))
+                        .size(16.0),
+                );
+            } else if self.script.is_none() {
+                ui.label(RichText::new(format!("Dalga: {}", self.wave)).size(20.0));
+            }
+            if let Some(run) = &self.daily {
+                let attempt = if run.official {
+                    "Resmi deneme"
+                } else {
+                    "Antrenman"
+                };
+                ui.label(
+                    RichText::new(format!("Günlük Görev {} ({})", run.challenge.date, attempt))
+                        .size(16.0),
+                );
+            }
+            if !self.mutators.is_empty() {
+                ui.label(
+                    RichText::new(self.mutators.names())
+                        .size(16.0)
+                        .color(egui::Color32::from_rgb(200, 120, 0)),
+                );
+            }
+            let difficulty = match &self.director {
+                Some(director) => format!(
+                    "Zorluk: {} (tempo x{:.2})",
+                    self.settings.difficulty.name(),
+                    director.pace()
+                ),
+                None => format!("Zorluk: {}", self.settings.difficulty.name()),
+            };
+            ui.label(RichText::new(difficulty).size(16.0));
+
+            if let Some(script) = &self.script {
+                ui.label(RichText::new(&script.level.name).size(16.0));
+                if let Some(objective) = &script.level.objective {
+                    ui.label(RichText::new(objective.describe()).size(16.0));
+                }
+                if let Some(name) = script.current_wave().and_then(|wave| wave.name.as_ref()) {
+                    ui.label(RichText::new(format!("Dalga: {}", name)).size(20.0));
+                }
+            }
+
+            if let Some(boss) = &self.boss {
+                self.draw_boss(ui, boss, now);
+            }
+
+            for enemy in &self.enemies {
+                let tex_key = match (&enemy.enemy_type, &enemy.state) {
+                    (EnemyType::Normal, EnemyState::Alive { .. }) => enemy.texture_key.clone(),
+                    (EnemyType::Normal, EnemyState::Firing { .. }) => {
                         "assets/sprite/enemy_fire.png".to_string()
                     }
                     (EnemyType::Normal, EnemyState::Dying(t0)) => {
@@ -346,285 +1555,820 @@ impl AdvancedApp {
                             "assets/sprite/elite_death-2.png".to_string()
                         }
                     }
+
+                    (EnemyType::Civilian, EnemyState::Dying(t0)) => {
+                        if now.duration_since(*t0) < Duration::from_millis(250) {
+                            "assets/sprite/civilian_death-1.png".to_string()
+                        } else {
+                            "assets/sprite/civilian_death-2.png".to_string()
+                        }
+                    }
+                    (EnemyType::Civilian, _) => enemy.texture_key.clone(),
+                };
+
+                if self.mutators.contains(Mutator::Invisible) {
+                    match enemy.state {
+                        EnemyState::Alive { .. } => continue,
+                        EnemyState::Firing { .. } => {
+                            let rect = self.mutators.target_rect(egui::Rect::from_min_size(
+                                egui::pos2(enemy.x, enemy.y),
+                                egui::vec2(enemy_display_width, enemy_size),
+                            ));
+                            let muzzle = rect.center_top() + egui::vec2(0.0, rect.height() * 0.35);
+                            ui.painter().circle_filled(
+                                muzzle,
+                                rect.width() * 0.12,
+                                egui::Color32::from_rgb(255, 170, 40),
+                            );
+                            ui.painter().circle_filled(
+                                muzzle,
+                                rect.width() * 0.06,
+                                egui::Color32::YELLOW,
+                            );
+                            continue;
+                        }
+                        EnemyState::Dying(_) => {}
+                    }
+                }
+
+                let tint = if frozen && !matches!(enemy.state, EnemyState::Dying(_)) {
+                    egui::Color32::from_rgb(160, 220, 255)
+                } else {
+                    egui::Color32::WHITE
                 };
 
                 if let Some(texture) = self.textures.get(&tex_key) {
-                    let rect = egui::Rect::from_min_size(
+                    let rect = self.mutators.target_rect(egui::Rect::from_min_size(
                         egui::pos2(enemy.x, enemy.y),
                         egui::vec2(enemy_display_width, enemy_size),
-                    );
+                    ));
                     ui.painter().image(
                         texture.id(),
                         rect,
                         egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                        egui::Color32::WHITE,
+                        tint,
                     );
                 }
             }
 
             for supply in &self.supply_boxes {
-                let tex_key = match (&supply.kind, &supply.state) {
-                    (SupplyBoxType::Health, SupplyBoxState::Active) => {
-                        "assets/sprite/supplybox_health.png"
-                    }
-                    (SupplyBoxType::Health, SupplyBoxState::Damaged(t)) => {
-                        if now.duration_since(*t) < Duration::from_millis(250) {
-                            "assets/sprite/supplybox_damaged.png"
-                        } else {
-                            "assets/sprite/supplybox_destroyed.png"
-                        }
-                    }
-                    (SupplyBoxType::Health, SupplyBoxState::Exploding(_)) => {
-                        "assets/sprite/supplybox_destroyed.png"
-                    }
-
-                    (SupplyBoxType::Tnt, SupplyBoxState::Active) => {
-                        "assets/sprite/supplybox_tnt.png"
-                    }
-                    (SupplyBoxType::Tnt, SupplyBoxState::Exploding(t)) => {
-                        if now.duration_since(*t) < Duration::from_millis(250) {
-                            "assets/sprite/supplybox_explosion.png"
-                        } else {
-                            "assets/sprite/supplybox_destroyed.png"
-                        }
-                    }
-                    (SupplyBoxType::Tnt, SupplyBoxState::Damaged(_)) => {
-                        "assets/sprite/supplybox_destroyed.png"
-                    }
-
-                    (_, SupplyBoxState::Destroyed(_)) => "assets/sprite/supplybox_destroyed.png",
-                };
-
-                if let Some(texture) = self.textures.get(tex_key) {
-                    let rect = egui::Rect::from_min_size(
-                        egui::pos2(supply.x, supply.y),
-                        egui::vec2(box_display_width, box_size),
-                    );
+                if let Some(texture) = self.textures.get(supply.sprite(now)) {
+                    let rect = supply.draw_rect(now, egui::vec2(box_display_width, box_size));
                     ui.painter().image(
                         texture.id(),
                         rect,
                         egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
-                        egui::Color32::WHITE,
+                        supply.tint(now),
                     );
                 }
             }
-        });
-
-        if !self.show_intro && !self.game_over {
-            let mut clicked_pos = None;
 
-            ctx.input(|i| {
-                if i.pointer.primary_clicked() {
-                    self.play_sound("assets/sound/gunshot.mp3");
-                    clicked_pos = i.pointer.interact_pos();
-                }
-            });
-
-            if let Some(pos) = clicked_pos {
-                self.handle_click_on_enemy(pos.x, pos.y);
+            for wave in &self.shockwaves {
+                let progress = now.duration_since(wave.start).as_secs_f32() / 0.4;
+                let alpha = ((1.0 - progress) * 255.0).clamp(0.0, 255.0) as u8;
+                ui.painter().circle_stroke(
+                    wave.center,
+                    wave.radius * progress.min(1.0),
+                    (
+                        4.0,
+                        egui::Color32::from_rgba_unmultiplied(255, 140, 0, alpha),
+                    ),
+                );
+            }
 
-                for supply in &mut self.supply_boxes {
-                    if let SupplyBoxState::Active = supply.state {
-                        let rect = egui::Rect::from_min_size(
-                            egui::pos2(supply.x, supply.y),
-                            egui::vec2(box_display_width, box_size),
-                        );
-                        if rect.contains(pos) {
-                            match supply.kind {
-                                SupplyBoxType::Health => {
-                                    supply.state = SupplyBoxState::Damaged(Instant::now());
-
-                                    self.visible_time += 20;
-                                    self.visible_time = self.visible_time.clamp(0, 60);
-
-                                    let index = rand::thread_rng().gen_range(1..=3);
-                                    let sound =
-                                        format!("assets/sound/supplybox_damage-{}.mp3", index);
-                                    self.play_sound(&sound);
-                                }
-
-                                SupplyBoxType::Tnt => {
-                                    supply.state = SupplyBoxState::Exploding(Instant::now());
-
-                                    self.visible_time -= 5;
-                                    self.visible_time = self.visible_time.clamp(0, 60);
-
-                                    let special = rand::thread_rng().gen_bool(0.01);
-                                    let sound = if special {
-                                        "assets/sound/supplybox_explosion-special.mp3".to_string()
-                                    } else {
-                                        "assets/sound/supplybox_explosion.mp3".to_string()
-                                    };
-                                    self.play_sound(&sound);
-
-                                    self.explode_tnt();
-                                }
-                            }
+            self.juice.draw_world(ui.painter(), now);
+            self.draw_effects(ui, real_now);
+
+            if let Some((t0, chain)) = self.chain_banner
+                && now.duration_since(t0) < Duration::from_millis(1500)
+            {
+                ui.painter().text(
+                    egui::pos2(400.0, 320.0),
+                    egui::Align2::CENTER_CENTER,
+                    format!("Zincir x{}!", chain),
+                    egui::FontId::proportional(30.0),
+                    egui::Color32::from_rgb(255, 140, 0),
+                );
+            }
 
-                            break;
-                        }
-                    }
-                }
+            if let Some((t0, reward)) = self.boss_defeated_at
+                && now.duration_since(t0) < Duration::from_secs(3)
+            {
+                ui.painter().text(
+                    egui::pos2(400.0, 250.0),
+                    egui::Align2::CENTER_CENTER,
+                    format!("Boss Yenildi! +{}", reward),
+                    egui::FontId::proportional(36.0),
+                    egui::Color32::GOLD,
+                );
             }
+        });
 
-            ctx.output_mut(|o| o.cursor_icon = egui::CursorIcon::None);
+        ctx.transform_layer_shapes(
+            egui::LayerId::background(),
+            TSTransform::from_translation(self.juice.shake_offset(now)),
+        );
 
-            if let Some(pos) = ctx.input(|i| i.pointer.hover_pos()) {
-                let painter = ctx.layer_painter(egui::LayerId::new(
-                    egui::Order::Foreground,
-                    egui::Id::new("crosshair"),
-                ));
-                let size = 15.0;
-                let gap = 5.0;
-                let color = egui::Color32::RED;
+        if !self.show_intro && !self.game_over && !self.shopping {
+            if !self.replaying {
+                ctx.output_mut(|o| o.cursor_icon = egui::CursorIcon::None);
+            }
 
-                painter.line_segment(
-                    [
-                        pos - egui::vec2(size + gap, 0.0),
-                        pos - egui::vec2(gap, 0.0),
-                    ],
-                    (2.0, color),
-                );
-                painter.line_segment(
-                    [
-                        pos + egui::vec2(gap, 0.0),
-                        pos + egui::vec2(size + gap, 0.0),
-                    ],
-                    (2.0, color),
-                );
-                painter.line_segment(
-                    [
-                        pos - egui::vec2(0.0, size + gap),
-                        pos - egui::vec2(0.0, gap),
-                    ],
-                    (2.0, color),
-                );
-                painter.line_segment(
-                    [
-                        pos + egui::vec2(0.0, gap),
-                        pos + egui::vec2(0.0, size + gap),
-                    ],
-                    (2.0, color),
+            let painter = ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                egui::Id::new("crosshair"),
+            ));
+            self.juice.draw_overlay(&painter, ctx.screen_rect(), now);
+            if let Some(ghost) = &self.ghost
+                && let Some(pos) = ghost.app.pointer
+            {
+                draw_crosshair(
+                    &painter,
+                    pos,
+                    egui::Color32::from_rgba_unmultiplied(255, 255, 255, 90),
                 );
             }
+            if let Some(pos) = self.pointer {
+                draw_crosshair(&painter, pos, egui::Color32::RED);
+            }
         }
 
-        let elapsed_secs = self.game_time.as_secs();
-        let prev_elapsed_secs = self.game_time.saturating_sub(delta).as_secs();
-
-        if elapsed_secs > prev_elapsed_secs {
-            self.visible_time -= 1;
-            self.visible_time = self.visible_time.clamp(0, 60);
+        if self.juice.is_active() {
+            ctx.request_repaint();
         }
-
         ctx.request_repaint_after(Duration::from_millis(100));
         signal
     }
 
+    fn run_default_spawns(&mut self, now: Instant) {
+        if now >= self.next_enemy_spawn_time {
+            self.spawn_enemy();
+            self.next_enemy_spawn_time =
+                now + self.difficulty.spawn_delay(self.pace(), &mut self.rng);
+        }
+
+        let boss_active = self.boss.as_ref().is_some_and(|boss| boss.is_alive());
+
+        if now >= self.next_elite_spawn_time
+            && self.game_time >= Duration::from_secs(30)
+            && !boss_active
+        {
+            self.spawn_elite();
+        }
+
+        if !self.boss_spawned && (self.game_time >= Duration::from_secs(60) || self.score >= 100) {
+            self.boss = Some(Boss::new(now));
+            self.boss_spawned = true;
+        }
+
+        let total_elapsed = now.duration_since(self.start_time);
+
+        if now >= self.next_supply_time && total_elapsed >= Duration::from_secs(10) {
+            let kind = SupplyBoxType::roll(self.supply_tier(), &mut self.rng);
+            let box_x = self.rng.gen_range(0.0..700.0);
+            let box_y = self.rng.gen_range(0.0..450.0);
+            if self.allows_supply(kind) {
+                self.supply_boxes
+                    .push(SupplyBox::new(kind, box_x, box_y, now));
+            }
+            self.next_supply_time = now + Duration::from_secs_f32(self.rng.gen_range(5.0..=8.0));
+        }
+    }
+
+    fn run_script(&mut self) {
+        let Some(script) = &mut self.script else {
+            return;
+        };
+        let due = script.due(self.game_time.as_secs_f32());
+        for spawn in due {
+            self.spawn_scripted(spawn);
+        }
+    }
+
     fn spawn_enemy(&mut self) {
-        let now = Instant::now();
-        let mut rng = rand::thread_rng();
+        let rng = &mut self.rng;
         let x = rng.gen_range(0.0..750.0);
         let y = rng.gen_range(0.0..500.0);
-        let texture_key = format!("assets/sprite/enemy-{}.png", rng.gen_range(1..=2));
-        let next_fire = now + Duration::from_secs_f32(1.0);
+        let civilian_chance = self
+            .difficulty
+            .civilian_chance(self.game_time.as_secs_f64() / 120.0);
+        let elite_chance = if self.mutators.contains(Mutator::ElitesOnly) {
+            1.0
+        } else if self.survival.is_some() {
+            survival::elite_share(self.game_time.as_secs_f32())
+        } else {
+            0.0
+        };
+        let enemy_type = if rng.gen_bool(elite_chance) {
+            EnemyType::Elite
+        } else if rng.gen_bool(civilian_chance) {
+            EnemyType::Civilian
+        } else {
+            EnemyType::Normal
+        };
 
-        self.enemies.push(Enemy {
-            x,
-            y,
-            texture_key,
-            state: EnemyState::Alive {
-                next_fire,
-                last_fired: None,
-            },
-            enemy_type: EnemyType::Normal,
-            hitpoints: 1,
-            spawn_time: now,
+        self.add_enemy(enemy_type, x, y, MovePath::Static);
+    }
+
+    fn spawn_scripted(&mut self, spawn: ScheduledSpawn) {
+        let enemy_type = match spawn.kind {
+            SpawnKind::Enemy => EnemyType::Normal,
+            SpawnKind::Elite => EnemyType::Elite,
+            SpawnKind::Civilian => EnemyType::Civilian,
+            SpawnKind::Health
+            | SpawnKind::Tnt
+            | SpawnKind::SlowMotion
+            | SpawnKind::Freeze
+            | SpawnKind::DoublePoints
+            | SpawnKind::Ammo
+            | SpawnKind::Shield
+            | SpawnKind::Supply => {
+                let (x, y) = spawn.pick_position(700.0, 450.0, &mut self.rng);
+                let kind = match spawn.kind {
+                    SpawnKind::Health => SupplyBoxType::Health,
+                    SpawnKind::Tnt => SupplyBoxType::Tnt,
+                    SpawnKind::SlowMotion => SupplyBoxType::SlowMotion,
+                    SpawnKind::Freeze => SupplyBoxType::Freeze,
+                    SpawnKind::DoublePoints => SupplyBoxType::DoublePoints,
+                    SpawnKind::Ammo => SupplyBoxType::Ammo,
+                    SpawnKind::Shield => SupplyBoxType::Shield,
+                    _ => SupplyBoxType::roll(self.supply_tier(), &mut self.rng),
+                };
+                self.supply_boxes
+                    .push(SupplyBox::new(kind, x, y, self.sim_now));
+                return;
+            }
+            SpawnKind::Boss => {
+                self.boss = Some(Boss::new(self.sim_now));
+                self.boss_spawned = true;
+                return;
+            }
+        };
+
+        let (x, y) = spawn.pick_position(750.0, 500.0, &mut self.rng);
+        self.add_enemy(enemy_type, x, y, spawn.path);
+    }
+
+    fn add_enemy(&mut self, enemy_type: EnemyType, x: f32, y: f32, path: MovePath) {
+        let mut enemy = Enemy::new(enemy_type, x, y, path, self.sim_now, &mut self.rng);
+        if let EnemyType::Elite = enemy_type {
+            enemy.hitpoints = self.difficulty.elite_hitpoints;
+        }
+        self.events.emit(GameEvent::EnemySpawned {
+            enemy_type,
+            pos: enemy.center(),
         });
+        self.enemies.push(enemy);
     }
 
     fn spawn_elite(&mut self) {
-        let elite = Enemy::new_elite();
+        let mut elite = Enemy::new_elite(self.sim_now, &mut self.rng);
+        elite.hitpoints = self.difficulty.elite_hitpoints;
+        self.events.emit(GameEvent::EnemySpawned {
+            enemy_type: EnemyType::Elite,
+            pos: elite.center(),
+        });
         self.enemies.push(elite);
 
-        let delay = rand::thread_rng().gen_range(4.0..=8.0);
-        self.next_elite_spawn_time = Instant::now() + Duration::from_secs_f32(delay);
+        let delay = self.rng.gen_range(4.0..=8.0);
+        self.next_elite_spawn_time = self.sim_now + Duration::from_secs_f32(delay);
+    }
+
+    fn draw_shop(&mut self, ui: &mut egui::Ui) {
+        ui.vertical_centered(|ui| {
+            ui.add_space(60.0);
+            ui.heading(RichText::new(format!("Dalga {} Tamamlandı!", self.wave)).size(32.0));
+            ui.add_space(10.0);
+            ui.label(RichText::new(format!("Jeton: {}", self.coins)).size(24.0));
+            ui.add_space(20.0);
+
+            for (index, perk) in ALL_PERKS.into_iter().enumerate() {
+                let level = self.upgrades.level(perk);
+                let cost = self.upgrades.next_cost(perk);
+                ui.label(
+                    RichText::new(format!("{} ({}/{})", perk.name(), level, perk.max_level()))
+                        .size(20.0),
+                );
+                ui.label(
+                    RichText::new(perk.description())
+                        .italics()
+                        .size(14.0)
+                        .color(egui::Color32::GRAY),
+                );
+                let text = match cost {
+                    Some(cost) => format!("Satın Al - {} Jeton", cost),
+                    None => "Maksimum".to_string(),
+                };
+                let affordable = cost.is_some_and(|cost| cost <= self.coins);
+                if ui
+                    .add_enabled(
+                        affordable && !self.replaying,
+                        egui::Button::new(RichText::new(text).size(16.0)),
+                    )
+                    .clicked()
+                {
+                    self.pending_action = Some(ShopAction::Buy(index));
+                }
+                ui.add_space(8.0);
+            }
+
+            ui.add_space(20.0);
+            if ui
+                .button(RichText::new("Sonraki Dalga").size(20.0))
+                .clicked()
+            {
+                self.pending_action = Some(ShopAction::NextWave);
+            }
+        });
+    }
+
+    fn buy_perk(&mut self, perk: Perk) {
+        if let Some(cost) = self.upgrades.next_cost(perk)
+            && cost <= self.coins
+        {
+            self.coins -= cost;
+            self.upgrades.push(perk);
+            self.params = self.upgrades.params();
+        }
+    }
+
+    fn save_replay(&mut self) {
+        let Some(frames) = self.recording.take() else {
+            return;
+        };
+        let replay = Replay::new(
+            self.replay_kind(),
+            self.seed,
+            self.settings,
+            self.score,
+            frames,
+        );
+        replay.save();
+        replay.save_if_best();
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.sounds.set_muted(muted);
+    }
+
+    fn start_next_wave(&mut self, now: Instant) {
+        self.shopping = false;
+        self.wave += 1;
+        self.wave_end = self.game_time + WAVE_LENGTH;
+        self.ammo = self.params.magazine_size;
+        self.reload_until = None;
+        self.next_enemy_spawn_time = now + self.difficulty.spawn_delay(self.pace(), &mut self.rng);
+        self.next_elite_spawn_time = now + Duration::from_secs_f32(self.rng.gen_range(4.0..=8.0));
+        self.next_supply_time = now + Duration::from_secs_f32(self.rng.gen_range(5.0..=8.0));
+    }
+
+    fn collect_supply(&mut self, kind: SupplyBoxType, now: Instant) {
+        self.events.emit(GameEvent::SupplyCollected { kind });
+        let spec = kind.spec();
+        match kind {
+            SupplyBoxType::Health => {
+                self.visible_time += 20;
+                self.visible_time = self.visible_time.clamp(0, self.params.max_time);
+            }
+            SupplyBoxType::Ammo => {
+                self.ammo = self.params.magazine_size;
+                self.reload_until = None;
+            }
+            _ => {}
+        }
+
+        if let Some(duration) = spec.duration {
+            match self.effects.iter_mut().find(|effect| effect.kind == kind) {
+                Some(effect) => effect.until = now + duration,
+                None => self.effects.push(ActiveEffect {
+                    kind,
+                    until: now + duration,
+                }),
+            }
+        }
+    }
+
+    fn drop_supply(&mut self, kind: SupplyBoxType, origin: egui::Pos2) {
+        let x = (origin.x - 30.0 + self.rng.gen_range(-40.0..=40.0)).clamp(0.0, 700.0);
+        let y = (origin.y - 30.0 + self.rng.gen_range(10.0..=40.0)).clamp(0.0, 450.0);
+        if !self.allows_supply(kind) {
+            return;
+        }
+        let mut supply = SupplyBox::new(kind, x, y, self.sim_now);
+        supply.dropped_from = Some(origin);
+        self.supply_boxes.push(supply);
+    }
+
+    fn allows_supply(&self, kind: SupplyBoxType) -> bool {
+        kind != SupplyBoxType::Health || !self.mutators.contains(Mutator::NoHealth)
+    }
+
+    fn effect_active(&self, kind: SupplyBoxType) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    fn score_multiplier(&self) -> f32 {
+        let double = if self.effect_active(SupplyBoxType::DoublePoints) {
+            2.0
+        } else {
+            1.0
+        };
+        double * self.mutators.score_factor()
+    }
+
+    fn supply_tier(&self) -> usize {
+        self.difficulty.supply_tier + (self.game_time.as_secs() / 30) as usize
+    }
+
+    fn pace(&self) -> f32 {
+        let pace = self
+            .director
+            .as_ref()
+            .map_or(1.0, |director| director.pace());
+        match self.survival {
+            Some(_) => pace * survival::intensity(self.game_time.as_secs_f32()),
+            None => pace,
+        }
+    }
+
+    fn draw_leaderboard(&self, ui: &mut egui::Ui, board: &SurvivalBoard) {
+        ui.add_space(10.0);
+        ui.label(
+            RichText::new(format!(
+                "Süre: {}",
+                survival::format_time(self.game_time.as_secs_f32())
+            ))
+            .size(24.0),
+        );
+        ui.add_space(10.0);
+        egui::Grid::new("survival_board")
+            .spacing([30.0, 4.0])
+            .show(ui, |ui| {
+                for (rank, entry) in board.entries.iter().enumerate() {
+                    let color = if self.survival_rank == Some(rank) {
+                        egui::Color32::GOLD
+                    } else {
+                        ui.visuals().text_color()
+                    };
+                    ui.label(RichText::new(format!("{}.", rank + 1)).color(color));
+                    ui.label(RichText::new(survival::format_time(entry.secs)).color(color));
+                    ui.label(RichText::new(format!("{} puan", entry.score)).color(color));
+                    ui.label(RichText::new(entry.difficulty.name()).color(color));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn draw_daily_history(&self, ui: &mut egui::Ui, run: &DailyRun) {
+        ui.add_space(10.0);
+        if !run.official {
+            ui.label(
+                RichText::new("Bugünün resmi denemesi kullanıldı, bu tur kaydedilmedi.")
+                    .italics()
+                    .color(egui::Color32::GRAY),
+            );
+        }
+        ui.add_space(10.0);
+        egui::Grid::new("daily_history")
+            .spacing([30.0, 4.0])
+            .show(ui, |ui| {
+                for entry in run.history.entries.iter().take(7) {
+                    let color = if entry.date == run.challenge.date {
+                        egui::Color32::GOLD
+                    } else {
+                        ui.visuals().text_color()
+                    };
+                    ui.label(RichText::new(&entry.date).color(color));
+                    if entry.in_progress {
+                        ui.label(RichText::new("Yarıda kaldı").color(color));
+                        ui.label("");
+                    } else {
+                        ui.label(RichText::new(format!("{} puan", entry.score)).color(color));
+                        ui.label(RichText::new(survival::format_time(entry.secs)).color(color));
+                    }
+                    ui.label(RichText::new(entry.mutators.names()).color(color));
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn draw_effects(&self, ui: &egui::Ui, now: Instant) {
+        if self.effect_active(SupplyBoxType::Shield) {
+            ui.painter().rect_stroke(
+                ui.max_rect().shrink(2.0),
+                0.0,
+                (4.0, SupplyBoxType::Shield.spec().color),
+            );
+        }
+
+        for (i, effect) in self.effects.iter().enumerate() {
+            let spec = effect.kind.spec();
+            let remaining = effect.until.saturating_duration_since(now).as_secs_f32();
+            ui.painter().text(
+                egui::pos2(ui.max_rect().right() - 10.0, 10.0 + i as f32 * 24.0),
+                egui::Align2::RIGHT_TOP,
+                format!("{} {:.1} sn", spec.name, remaining),
+                egui::FontId::proportional(18.0),
+                spec.color,
+            );
+        }
+    }
+
+    fn draw_boss(&self, ui: &egui::Ui, boss: &Boss, now: Instant) {
+        let tex_key = match boss.state {
+            BossState::Dying(t0) => {
+                if now.duration_since(t0) < Duration::from_millis(750) {
+                    "assets/sprite/elite_death-1.png"
+                } else {
+                    "assets/sprite/elite_death-2.png"
+                }
+            }
+            BossState::Firing { .. } => "assets/sprite/elite_fire.png",
+            _ => "assets/sprite/elite-1.png",
+        };
+
+        if let Some(texture) = self.textures.get(tex_key) {
+            ui.painter().image(
+                texture.id(),
+                boss.rect(),
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::from_rgb(255, 140, 140),
+            );
+        }
+
+        if boss.is_exposed() {
+            let pulse = (now.duration_since(self.start_time).as_secs_f32() * 8.0).sin() * 4.0;
+            ui.painter().circle_stroke(
+                boss.weak_point().center(),
+                18.0 + pulse,
+                (3.0, egui::Color32::YELLOW),
+            );
+        }
+
+        if !boss.is_alive() {
+            return;
+        }
+
+        let bar = egui::Rect::from_min_size(egui::pos2(200.0, 12.0), egui::vec2(400.0, 18.0));
+        let fill = bar.width() * boss.hitpoints as f32 / BOSS_MAX_HP as f32;
+        ui.painter()
+            .rect_filled(bar, 3.0, egui::Color32::from_gray(60));
+        ui.painter().rect_filled(
+            egui::Rect::from_min_size(bar.min, egui::vec2(fill, bar.height())),
+            3.0,
+            egui::Color32::DARK_RED,
+        );
+        let phase = match boss.phase() {
+            BossPhase::One => 1,
+            BossPhase::Two => 2,
+            BossPhase::Three => 3,
+        };
+        ui.painter().text(
+            bar.center(),
+            egui::Align2::CENTER_CENTER,
+            format!("BOSS - Faz {}", phase),
+            egui::FontId::proportional(14.0),
+            egui::Color32::WHITE,
+        );
+    }
+
+    fn handle_click_on_boss(&mut self, pos: egui::Pos2) -> bool {
+        let Some(boss) = &mut self.boss else {
+            return false;
+        };
+        match boss.hit(pos, self.sim_now) {
+            BossHit::Killed => self.defeat_boss(),
+            BossHit::WeakPoint => self.events.emit(GameEvent::BossWounded),
+            BossHit::Armored => {}
+            BossHit::Miss => return false,
+        }
+        true
+    }
+
+    fn defeat_boss(&mut self) {
+        let multiplier = self.score_multiplier();
+        let reward = scaled_points(&mut self.score_fraction, 100.0 * multiplier);
+        self.score += reward;
+        self.coins += 25;
+        self.visible_time += 30;
+        self.visible_time = self.visible_time.clamp(0, self.params.max_time);
+        self.boss_defeated_at = Some((self.sim_now, reward));
+        self.events.emit(GameEvent::BossDefeated);
     }
 
-    fn explode_tnt(&mut self) {
+    fn detonate_tnt(&mut self, index: usize, chain: u32) {
+        let now = self.sim_now;
+        let radius = self.params.tnt_radius;
+        let Some(supply) = self.supply_boxes.get_mut(index) else {
+            return;
+        };
+        supply.state = SupplyBoxState::Exploding(now);
+        let center = supply.center();
+
+        for other in &mut self.supply_boxes {
+            if other.is_hittable() && other.center().distance(center) <= radius {
+                other.hitpoints = other.hitpoints.saturating_sub(1);
+                other.state = match other.kind {
+                    SupplyBoxType::Tnt => SupplyBoxState::Primed {
+                        detonate_at: now + Duration::from_millis(250),
+                        chain: chain + 1,
+                    },
+                    _ if other.hitpoints > 0 => SupplyBoxState::Damaged(now),
+                    _ => SupplyBoxState::Destroyed(now),
+                };
+            }
+        }
+
+        let kills = self.explode_tnt(center, radius, chain);
+        self.events.emit(GameEvent::TntExploded {
+            center,
+            radius,
+            chain,
+            kills,
+            special: self.rng.gen_bool(0.01),
+        });
+    }
+
+    fn explode_tnt(&mut self, center: egui::Pos2, radius: f32, chain: u32) -> u32 {
+        let multiplier = self.score_multiplier();
+        let mut drops = Vec::new();
+        let mut kills = 0;
         for enemy in &mut self.enemies {
+            if enemy.center().distance(center) > radius {
+                continue;
+            }
             if let EnemyState::Alive { .. } | EnemyState::Firing { .. } = enemy.state {
-                enemy.state = EnemyState::Dying(Instant::now());
+                let died = enemy.take_hit(self.sim_now);
+                self.events.emit(GameEvent::EnemyHit {
+                    enemy_type: enemy.enemy_type,
+                    pos: enemy.center(),
+                    killed: died,
+                });
+                if !died {
+                    continue;
+                }
+                self.events.emit(GameEvent::EnemyKilled {
+                    enemy_type: enemy.enemy_type,
+                    pos: enemy.center(),
+                    cause: KillCause::Explosion,
+                    reaction: None,
+                    special: false,
+                });
 
-                let bonus = match enemy.enemy_type {
-                    EnemyType::Normal => 3,
-                    EnemyType::Elite => 15,
-                };
-                self.score += bonus;
+                let bonus = chain
+                    * match enemy.enemy_type {
+                        EnemyType::Normal => 3,
+                        EnemyType::Elite => 15,
+                        EnemyType::Civilian => {
+                            self.friendly_fire += 1;
+                            self.score = self.score.saturating_sub(5);
+                            self.visible_time =
+                                (self.visible_time - 5).clamp(0, self.params.max_time);
+                            self.events.emit(GameEvent::ScoreAwarded {
+                                points: -5,
+                                pos: enemy.center(),
+                            });
+                            continue;
+                        }
+                    };
+                kills += 1;
+                let points = scaled_points(&mut self.score_fraction, bonus as f32 * multiplier);
+                self.score += points;
+                self.coins += bonus;
+                self.events.emit(GameEvent::ScoreAwarded {
+                    points: points as i64,
+                    pos: enemy.center(),
+                });
+                if let Some(kind) = enemy.enemy_type.roll_loot(&mut self.rng) {
+                    drops.push((kind, enemy.center()));
+                }
             }
         }
+
+        for (kind, origin) in drops {
+            self.drop_supply(kind, origin);
+        }
+
+        if let Some(boss) = &mut self.boss
+            && boss.rect().center().distance(center) <= radius + boss.rect().width() / 2.0
+            && let BossHit::Killed = boss.damage(5, self.sim_now)
+        {
+            self.defeat_boss();
+        }
+        kills
     }
 
-    fn handle_click_on_enemy(&mut self, x: f32, y: f32) {
+    fn handle_click_on_enemy(&mut self, x: f32, y: f32) -> bool {
+        let multiplier = self.score_multiplier();
         for enemy in &mut self.enemies {
-            let rect =
-                egui::Rect::from_min_size(egui::pos2(enemy.x, enemy.y), egui::vec2(75.0, 117.85));
+            let rect = self.mutators.target_rect(egui::Rect::from_min_size(
+                egui::pos2(enemy.x, enemy.y),
+                egui::vec2(75.0, 117.85),
+            ));
             if rect.contains(egui::pos2(x, y)) {
                 match enemy.state {
                     EnemyState::Alive { .. } | EnemyState::Firing { .. } => {
-                        let died = enemy.take_hit();
+                        let died = enemy.take_hit(self.sim_now);
+                        self.events.emit(GameEvent::EnemyHit {
+                            enemy_type: enemy.enemy_type,
+                            pos: egui::pos2(x, y),
+                            killed: died,
+                        });
                         if died {
+                            let reaction = if let EnemyType::Civilian = enemy.enemy_type {
+                                self.friendly_fire += 1;
+                                self.score = self.score.saturating_sub(5);
+                                self.visible_time =
+                                    (self.visible_time - 5).clamp(0, self.params.max_time);
+                                self.streak = 0;
+                                None
+                            } else {
+                                self.streak += 1;
+                                self.best_streak = self.best_streak.max(self.streak);
+                                Some(self.sim_now.duration_since(enemy.spawn_time))
+                            };
+                            self.events.emit(GameEvent::EnemyKilled {
+                                enemy_type: enemy.enemy_type,
+                                pos: enemy.center(),
+                                cause: KillCause::Shot,
+                                reaction,
+                                special: self.rng.gen_bool(0.05),
+                            });
+
                             let bonus = match enemy.enemy_type {
                                 EnemyType::Normal => 1,
                                 EnemyType::Elite => 5,
+                                EnemyType::Civilian => 0,
                             };
-                            self.score += bonus;
-
-                            let mut rng = rand::thread_rng();
-                            let chance: f64 = rng.r#gen();
-
-                            match enemy.enemy_type {
-                                EnemyType::Normal => {
-                                    if chance < 0.05 {
-                                        self.play_sound("assets/sound/enemy_death-special.mp3");
-                                    } else {
-                                        let index = rng.gen_range(1..=3);
-                                        let path =
-                                            format!("assets/sound/enemy_death-{}.wav", index);
-                                        self.play_sound(&path);
-                                    }
-                                }
-                                EnemyType::Elite => {
-                                    if chance < 0.05 {
-                                        self.play_sound("assets/sound/elite_death-special.mp3");
-                                    } else {
-                                        let index = rng.gen_range(1..=2);
-                                        let path =
-                                            format!("assets/sound/elite_death-{}.mp3", index);
-                                        self.play_sound(&path);
-                                    }
-                                }
+                            let points =
+                                scaled_points(&mut self.score_fraction, bonus as f32 * multiplier);
+                            self.score += points;
+                            self.coins += bonus;
+                            self.events.emit(GameEvent::ScoreAwarded {
+                                points: match enemy.enemy_type {
+                                    EnemyType::Civilian => -5,
+                                    _ => points as i64,
+                                },
+                                pos: enemy.center(),
+                            });
+                            let loot = enemy
+                                .enemy_type
+                                .roll_loot(&mut self.rng)
+                                .map(|kind| (kind, enemy.center()));
+
+                            if let Some((kind, origin)) = loot {
+                                self.drop_supply(kind, origin);
                             }
                         }
-                        break;
+                        return true;
                     }
                     _ => {}
                 }
             }
         }
+        false
     }
+}
 
-    fn play_sound(&self, path: &str) {
-        if let Ok(file) = std::fs::File::open(path) {
-            let buffered = BufReader::new(file);
-            if let Ok(source) = Decoder::new(buffered) {
-                if let Ok(sink) = Sink::try_new(&self.audio_handle) {
-                    sink.append(source);
-                    sink.detach();
-                }
-            }
-        }
-    }
+fn scaled_points(fraction: &mut f32, points: f32) -> u32 {
+    *fraction += points;
+    let whole = fraction.floor();
+    *fraction -= whole;
+    whole as u32
+}
+
+fn draw_crosshair(painter: &egui::Painter, pos: egui::Pos2, color: egui::Color32) {
+    let size = 15.0;
+    let gap = 5.0;
+
+    painter.line_segment(
+        [
+            pos - egui::vec2(size + gap, 0.0),
+            pos - egui::vec2(gap, 0.0),
+        ],
+        (2.0, color),
+    );
+    painter.line_segment(
+        [
+            pos + egui::vec2(gap, 0.0),
+            pos + egui::vec2(size + gap, 0.0),
+        ],
+        (2.0, color),
+    );
+    painter.line_segment(
+        [
+            pos - egui::vec2(0.0, size + gap),
+            pos - egui::vec2(0.0, gap),
+        ],
+        (2.0, color),
+    );
+    painter.line_segment(
+        [
+            pos + egui::vec2(0.0, gap),
+            pos + egui::vec2(0.0, size + gap),
+        ],
+        (2.0, color),
+    );
 }
 
 fn load_image(ctx: &egui::Context, path: &str) -> TextureHandle {
@@ -635,3 +2379,135 @@ fn load_image(ctx: &egui::Context, path: &str) -> TextureHandle {
         egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &rgba);
     ctx.load_texture(path, color_image, TextureOptions::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field() -> egui::Rect {
+        egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0))
+    }
+
+    fn frame(dt_ms: u64) -> FrameInput {
+        FrameInput {
+            dt: Duration::from_millis(dt_ms),
+            ..FrameInput::default()
+        }
+    }
+
+    fn click(x: f32, y: f32) -> FrameInput {
+        FrameInput {
+            pointer: Some(egui::pos2(x, y)),
+            click: true,
+            ..frame(1)
+        }
+    }
+
+    fn started(seed: u64) -> AdvancedApp {
+        let mut app = AdvancedApp::with_sounds(Settings::default(), seed, SoundBoard::silent());
+        app.recording = None;
+        let events = app.step(&frame(4000), field());
+        assert!(events.contains(&GameEvent::RoundStarted));
+
+        let later = app.sim_now + Duration::from_secs(60);
+        app.next_enemy_spawn_time = later;
+        app.next_elite_spawn_time = later;
+        app.next_supply_time = later;
+        app
+    }
+
+    #[test]
+    fn shot_on_enemy_kills_and_scores() {
+        let mut app = started(7);
+        app.add_enemy(EnemyType::Normal, 100.0, 100.0, MovePath::Static);
+        app.events.drain();
+
+        let events = app.step(&click(130.0, 150.0), field());
+
+        assert!(events.contains(&GameEvent::ShotFired {
+            pos: egui::pos2(130.0, 150.0),
+            hit: true,
+        }));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            GameEvent::EnemyKilled {
+                enemy_type: EnemyType::Normal,
+                cause: KillCause::Shot,
+                ..
+            }
+        )));
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, GameEvent::ScoreAwarded { points: 1, .. }))
+        );
+        assert_eq!(app.score, 1);
+    }
+
+    #[test]
+    fn tnt_reports_its_kills() {
+        let mut app = started(7);
+        app.add_enemy(EnemyType::Normal, 300.0, 250.0, MovePath::Static);
+        app.add_enemy(EnemyType::Normal, 480.0, 300.0, MovePath::Static);
+        app.add_enemy(EnemyType::Normal, 0.0, 0.0, MovePath::Static);
+        let mut tnt = SupplyBox::new(SupplyBoxType::Tnt, 400.0, 400.0, app.sim_now);
+        tnt.hitpoints = 1;
+        app.supply_boxes.push(tnt);
+
+        let events = app.step(&click(430.0, 430.0), field());
+
+        let kills = events.iter().find_map(|event| match event {
+            GameEvent::TntExploded { kills, chain, .. } => Some((*kills, *chain)),
+            _ => None,
+        });
+        assert_eq!(kills, Some((2, 1)));
+        let explosion_kills = events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    GameEvent::EnemyKilled {
+                        cause: KillCause::Explosion,
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(explosion_kills, 2);
+    }
+
+    #[test]
+    fn round_ends_when_time_runs_out() {
+        let mut app = started(7);
+        app.score = 12;
+        app.visible_time = 0;
+
+        let events = app.step(&frame(16), field());
+
+        assert!(events.contains(&GameEvent::RoundEnded { score: 12 }));
+        assert!(app.game_over);
+        assert!(app.step(&frame(16), field()).is_empty());
+    }
+
+    #[test]
+    fn same_seed_and_input_give_same_events() {
+        let inputs = [
+            frame(4000),
+            frame(900),
+            click(200.0, 200.0),
+            frame(1200),
+            click(420.0, 310.0),
+            frame(2500),
+            click(90.0, 380.0),
+        ];
+        let run = |seed| {
+            let mut app = AdvancedApp::with_sounds(Settings::default(), seed, SoundBoard::silent());
+            app.recording = None;
+            inputs
+                .iter()
+                .flat_map(|input| app.step(input, field()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(42), run(42));
+    }
+}
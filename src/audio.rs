@@ -0,0 +1,99 @@
+use crate::advanced::EnemyType;
+use crate::event::{GameEvent, KillCause, Subscriber};
+use crate::supply::SupplyBoxType;
+use rand::Rng;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use std::io::BufReader;
+
+pub struct SoundBoard {
+    muted: bool,
+    output: Option<(OutputStream, OutputStreamHandle)>,
+}
+
+impl SoundBoard {
+    pub fn new() -> Self {
+        SoundBoard {
+            muted: false,
+            output: OutputStream::try_default().ok(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn silent() -> Self {
+        SoundBoard {
+            muted: true,
+            output: None,
+        }
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    pub fn play(&self, path: &str) {
+        if self.muted {
+            return;
+        }
+        let Some((_, handle)) = &self.output else {
+            return;
+        };
+        if let Ok(file) = std::fs::File::open(path)
+            && let Ok(source) = Decoder::new(BufReader::new(file))
+            && let Ok(sink) = Sink::try_new(handle)
+        {
+            sink.append(source);
+            sink.detach();
+        }
+    }
+}
+
+impl Subscriber for SoundBoard {
+    fn on_event(&mut self, event: &GameEvent) {
+        let mut rng = rand::thread_rng();
+        match *event {
+            GameEvent::ShotFired { .. } => self.play("assets/sound/gunshot.mp3"),
+            GameEvent::EnemyKilled {
+                enemy_type,
+                cause: KillCause::Shot,
+                special,
+                ..
+            } => match (enemy_type, special) {
+                (EnemyType::Normal | EnemyType::Civilian, true) => {
+                    self.play("assets/sound/enemy_death-special.mp3")
+                }
+                (EnemyType::Normal | EnemyType::Civilian, false) => self.play(&format!(
+                    "assets/sound/enemy_death-{}.wav",
+                    rng.gen_range(1..=3)
+                )),
+                (EnemyType::Elite, true) => self.play("assets/sound/elite_death-special.mp3"),
+                (EnemyType::Elite, false) => self.play(&format!(
+                    "assets/sound/elite_death-{}.mp3",
+                    rng.gen_range(1..=2)
+                )),
+            },
+            GameEvent::EnemyFired { enemy_type, .. } => match enemy_type {
+                EnemyType::Normal | EnemyType::Civilian => self.play("assets/sound/enemy_fire.mp3"),
+                EnemyType::Elite => self.play("assets/sound/elite_fire.wav"),
+            },
+            GameEvent::BossWounded => self.play(&format!(
+                "assets/sound/elite_death-{}.mp3",
+                rng.gen_range(1..=2)
+            )),
+            GameEvent::BossDefeated => self.play("assets/sound/boss_victory.wav"),
+            GameEvent::SupplyDamaged { .. } => self.play(&format!(
+                "assets/sound/supplybox_damage-{}.mp3",
+                rng.gen_range(1..=3)
+            )),
+            GameEvent::SupplyCollected { kind } if kind != SupplyBoxType::Tnt => {
+                let sounds = kind.spec().sounds;
+                self.play(sounds[rng.gen_range(0..sounds.len())]);
+            }
+            GameEvent::TntExploded { special, .. } => self.play(if special {
+                "assets/sound/supplybox_explosion_special.mp3"
+            } else {
+                "assets/sound/supplybox_explosion.mp3"
+            }),
+            _ => {}
+        }
+    }
+}
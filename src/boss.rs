@@ -0,0 +1,244 @@
+use crate::resume;
+use eframe::egui;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+pub const BOSS_MAX_HP: u32 = 40;
+pub const BOSS_WIDTH: f32 = 150.0;
+pub const BOSS_HEIGHT: f32 = BOSS_WIDTH * (55.0 / 35.0);
+
+#[derive(Clone, Copy)]
+pub enum BossPhase {
+    One,
+    Two,
+    Three,
+}
+
+pub enum BossState {
+    Guarding { next_volley: Instant },
+    Firing { shots_left: u32, next_shot: Instant },
+    Exposed(Instant),
+    Dying(Instant),
+}
+
+pub enum BossHit {
+    Miss,
+    Armored,
+    WeakPoint,
+    Killed,
+}
+
+pub struct Boss {
+    pub x: f32,
+    pub y: f32,
+    pub hitpoints: u32,
+    pub state: BossState,
+    direction: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum BossStateSnapshot {
+    Guarding { volley_in: f32 },
+    Firing { shots_left: u32, shot_in: f32 },
+    Exposed(f32),
+    Dying(f32),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BossSnapshot {
+    x: f32,
+    y: f32,
+    hitpoints: u32,
+    state: BossStateSnapshot,
+    direction: f32,
+}
+
+impl Boss {
+    pub fn new(now: Instant) -> Self {
+        Boss {
+            x: 400.0 - BOSS_WIDTH / 2.0,
+            y: 80.0,
+            hitpoints: BOSS_MAX_HP,
+            state: BossState::Guarding {
+                next_volley: now + Duration::from_secs(2),
+            },
+            direction: 1.0,
+        }
+    }
+
+    pub fn snapshot(&self, now: Instant) -> BossSnapshot {
+        let state = match self.state {
+            BossState::Guarding { next_volley } => BossStateSnapshot::Guarding {
+                volley_in: resume::remaining(next_volley, now),
+            },
+            BossState::Firing {
+                shots_left,
+                next_shot,
+            } => BossStateSnapshot::Firing {
+                shots_left,
+                shot_in: resume::remaining(next_shot, now),
+            },
+            BossState::Exposed(t0) => BossStateSnapshot::Exposed(resume::elapsed(t0, now)),
+            BossState::Dying(t0) => BossStateSnapshot::Dying(resume::elapsed(t0, now)),
+        };
+        BossSnapshot {
+            x: self.x,
+            y: self.y,
+            hitpoints: self.hitpoints,
+            state,
+            direction: self.direction,
+        }
+    }
+
+    pub fn restore(snapshot: BossSnapshot, now: Instant) -> Self {
+        let state = match snapshot.state {
+            BossStateSnapshot::Guarding { volley_in } => BossState::Guarding {
+                next_volley: resume::after(volley_in, now),
+            },
+            BossStateSnapshot::Firing {
+                shots_left,
+                shot_in,
+            } => BossState::Firing {
+                shots_left,
+                next_shot: resume::after(shot_in, now),
+            },
+            BossStateSnapshot::Exposed(secs) => BossState::Exposed(resume::ago(secs, now)),
+            BossStateSnapshot::Dying(secs) => BossState::Dying(resume::ago(secs, now)),
+        };
+        Boss {
+            x: snapshot.x,
+            y: snapshot.y,
+            hitpoints: snapshot.hitpoints,
+            state,
+            direction: snapshot.direction,
+        }
+    }
+
+    pub fn phase(&self) -> BossPhase {
+        if self.hitpoints * 3 > BOSS_MAX_HP * 2 {
+            BossPhase::One
+        } else if self.hitpoints * 3 > BOSS_MAX_HP {
+            BossPhase::Two
+        } else {
+            BossPhase::Three
+        }
+    }
+
+    pub fn rect(&self) -> egui::Rect {
+        egui::Rect::from_min_size(
+            egui::pos2(self.x, self.y),
+            egui::vec2(BOSS_WIDTH, BOSS_HEIGHT),
+        )
+    }
+
+    pub fn weak_point(&self) -> egui::Rect {
+        egui::Rect::from_center_size(
+            egui::pos2(self.x + BOSS_WIDTH / 2.0, self.y + BOSS_HEIGHT * 0.45),
+            egui::vec2(36.0, 36.0),
+        )
+    }
+
+    pub fn is_exposed(&self) -> bool {
+        matches!(self.state, BossState::Exposed(_))
+    }
+
+    pub fn is_alive(&self) -> bool {
+        !matches!(self.state, BossState::Dying(_))
+    }
+
+    pub fn is_gone(&self, now: Instant) -> bool {
+        match self.state {
+            BossState::Dying(t0) => now.duration_since(t0) >= Duration::from_millis(1500),
+            _ => false,
+        }
+    }
+
+    pub fn update(&mut self, now: Instant, delta: Duration, rng: &mut impl Rng) -> u32 {
+        let mut shots = 0;
+        let phase = self.phase();
+
+        if let BossPhase::Three = phase
+            && self.is_alive()
+        {
+            self.x += self.direction * 120.0 * delta.as_secs_f32();
+            if self.x <= 0.0 || self.x >= 800.0 - BOSS_WIDTH {
+                self.x = self.x.clamp(0.0, 800.0 - BOSS_WIDTH);
+                self.direction = -self.direction;
+            }
+        }
+
+        match &mut self.state {
+            BossState::Guarding { next_volley } => {
+                if now >= *next_volley {
+                    let shots_left = match phase {
+                        BossPhase::One => 1,
+                        BossPhase::Two => 3,
+                        BossPhase::Three => 5,
+                    };
+                    self.state = BossState::Firing {
+                        shots_left,
+                        next_shot: now,
+                    };
+                }
+            }
+            BossState::Firing {
+                shots_left,
+                next_shot,
+            } => {
+                if now >= *next_shot {
+                    shots += 1;
+                    *shots_left -= 1;
+                    *next_shot = now + Duration::from_millis(250);
+                    if *shots_left == 0 {
+                        self.state = BossState::Exposed(now);
+                    }
+                }
+            }
+            BossState::Exposed(t0) => {
+                let window = match phase {
+                    BossPhase::One => 1500,
+                    BossPhase::Two => 1200,
+                    BossPhase::Three => 900,
+                };
+                if now.duration_since(*t0) >= Duration::from_millis(window) {
+                    let delay = match phase {
+                        BossPhase::One => rng.gen_range(1.8..=2.4),
+                        BossPhase::Two => rng.gen_range(1.4..=2.0),
+                        BossPhase::Three => rng.gen_range(0.8..=1.2),
+                    };
+                    self.state = BossState::Guarding {
+                        next_volley: now + Duration::from_secs_f32(delay),
+                    };
+                }
+            }
+            BossState::Dying(_) => {}
+        }
+
+        shots
+    }
+
+    pub fn hit(&mut self, pos: egui::Pos2, now: Instant) -> BossHit {
+        if !self.is_alive() || !self.rect().contains(pos) {
+            return BossHit::Miss;
+        }
+        if !self.is_exposed() || !self.weak_point().contains(pos) {
+            return BossHit::Armored;
+        }
+        self.damage(2, now)
+    }
+
+    pub fn damage(&mut self, amount: u32, now: Instant) -> BossHit {
+        if !self.is_alive() {
+            return BossHit::Miss;
+        }
+        if self.hitpoints > amount {
+            self.hitpoints -= amount;
+            BossHit::WeakPoint
+        } else {
+            self.hitpoints = 0;
+            self.state = BossState::Dying(now);
+            BossHit::Killed
+        }
+    }
+}
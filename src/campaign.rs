@@ -0,0 +1,72 @@
+use crate::level::{LEVEL_DIR, Level};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const CAMPAIGN_FILE: &str = "assets/campaign.toml";
+const PROGRESS_FILE: &str = "campaign.toml";
+
+#[derive(Deserialize, Default)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+}
+
+#[derive(Deserialize)]
+pub struct CampaignLevel {
+    pub file: String,
+    pub title: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct CampaignProgress {
+    #[serde(default)]
+    stars: BTreeMap<String, u8>,
+}
+
+impl Campaign {
+    pub fn load() -> Campaign {
+        std::fs::read_to_string(CAMPAIGN_FILE)
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn load_level(&self, index: usize) -> Result<Level, String> {
+        let entry = self
+            .levels
+            .get(index)
+            .ok_or_else(|| format!("Bölüm {} bulunamadı", index + 1))?;
+        Level::load(&Path::new(LEVEL_DIR).join(&entry.file))
+    }
+}
+
+impl CampaignProgress {
+    pub fn load() -> CampaignProgress {
+        storage::load_toml(PROGRESS_FILE)
+    }
+
+    pub fn stars(&self, campaign: &Campaign, index: usize) -> u8 {
+        campaign
+            .levels
+            .get(index)
+            .and_then(|level| self.stars.get(&level.file))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn is_unlocked(&self, campaign: &Campaign, index: usize) -> bool {
+        index == 0 || self.stars(campaign, index - 1) > 0
+    }
+
+    pub fn record(&mut self, campaign: &Campaign, index: usize, stars: u8) {
+        let Some(level) = campaign.levels.get(index) else {
+            return;
+        };
+        let best = self.stars.entry(level.file.clone()).or_insert(0);
+        if stars > *best {
+            *best = stars;
+            storage::save_toml(PROGRESS_FILE, self);
+        }
+    }
+}
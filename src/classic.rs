@@ -1,14 +1,26 @@
 // classic.rs
+use crate::achievement::{self, Achievement, AchievementTracker};
+use crate::advanced::EnemyType;
+use crate::audio::SoundBoard;
+use crate::difficulty::{DifficultyParams, Settings};
+use crate::event::{EventBus, GameEvent, KillCause, Subscriber};
+use crate::mutator::{DOUBLE_SPEED, FLASH_TIME, Mutator, MutatorSet};
+use crate::profile::{RoundSummary, RoundTally};
+use crate::replay::{self, FrameInput, Replay, ReplayKind};
+use crate::resume;
 use eframe::egui;
 use egui::{RichText, TextureHandle, TextureOptions};
 use image::GenericImageView;
 use image::io::Reader as ImageReader;
-use rand::Rng;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::BufReader;
 use std::time::{Duration, Instant};
 
+const DISPLAY_WIDTH: f32 = 75.0;
+const DISPLAY_HEIGHT: f32 = DISPLAY_WIDTH * (55.0 / 35.0);
+
 pub struct ClassicApp {
     score: u32,
     enemies: Vec<Enemy>,
@@ -19,9 +31,24 @@ pub struct ClassicApp {
     show_intro: bool,
     intro_start: Instant,
     restart_count: u32,
-    #[allow(dead_code)]
-    audio_stream: OutputStream,
-    audio_handle: OutputStreamHandle,
+    friendly_fire: u32,
+    time_penalty: Duration,
+    settings: Settings,
+    difficulty: DifficultyParams,
+    mutators: MutatorSet,
+    rng: ChaCha12Rng,
+    missed_one_shot: bool,
+    summary_taken: bool,
+    tally: RoundTally,
+    achievements: AchievementTracker,
+    events: EventBus,
+    sounds: SoundBoard,
+    seed: u64,
+    sim_now: Instant,
+    last_update: Instant,
+    pointer: Option<egui::Pos2>,
+    recording: Option<Vec<FrameInput>>,
+    replaying: bool,
 }
 
 enum EnemyState {
@@ -29,35 +56,280 @@ enum EnemyState {
     Dying(Instant),
 }
 
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum EnemyKind {
+    Hostile,
+    Civilian,
+}
+
 struct Enemy {
     x: f32,
     y: f32,
     texture_key: String,
+    kind: EnemyKind,
     state: EnemyState,
     spawn_time: Instant,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct ClassicSnapshot {
+    settings: Settings,
+    #[serde(default)]
+    seed: [u8; 8],
+    #[serde(default)]
+    rng_seed: [u8; 32],
+    #[serde(default)]
+    rng_word_pos: u64,
+    score: u32,
+    elapsed: f32,
+    intro_ago: Option<f32>,
+    next_spawn_in: f32,
+    friendly_fire: u32,
+    time_penalty: f32,
+    #[serde(default)]
+    tally: RoundTally,
+    enemies: Vec<EnemySnapshot>,
+    #[serde(default)]
+    recording: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum EnemyStateSnapshot {
+    Alive,
+    Dying(f32),
+}
+
+#[derive(Serialize, Deserialize)]
+struct EnemySnapshot {
+    x: f32,
+    y: f32,
+    texture_key: String,
+    kind: EnemyKind,
+    state: EnemyStateSnapshot,
+    age: f32,
+}
+
 impl ClassicApp {
-    pub fn new() -> Self {
-        let (stream, stream_handle) = OutputStream::try_default().unwrap();
-        let spawn_delay = Duration::from_secs_f32(rand::thread_rng().gen_range(0.5..=1.0));
+    pub fn new(settings: Settings) -> Self {
+        Self::seeded(settings, rand::random())
+    }
+
+    pub fn seeded(settings: Settings, seed: u64) -> Self {
+        Self::with_sounds(settings, seed, SoundBoard::new())
+    }
+
+    fn with_sounds(settings: Settings, seed: u64, sounds: SoundBoard) -> Self {
+        let difficulty = settings.difficulty.params();
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let spawn_delay = difficulty.spawn_delay(pace(settings.mutators), &mut rng);
+        let now = Instant::now();
         Self {
             score: 0,
             enemies: Vec::new(),
-            next_spawn_time: Instant::now() + spawn_delay,
+            next_spawn_time: now + spawn_delay,
             textures: HashMap::new(),
-            start_time: Instant::now(),
+            start_time: now,
             game_over: false,
             show_intro: true,
-            intro_start: Instant::now(),
+            intro_start: now,
             restart_count: 0,
-            audio_stream: stream,
-            audio_handle: stream_handle,
+            friendly_fire: 0,
+            time_penalty: Duration::ZERO,
+            settings,
+            difficulty,
+            mutators: settings.mutators,
+            rng,
+            missed_one_shot: false,
+            summary_taken: false,
+            tally: RoundTally::default(),
+            achievements: AchievementTracker::default(),
+            events: EventBus::default(),
+            sounds,
+            seed,
+            sim_now: now,
+            last_update: now,
+            pointer: None,
+            recording: Some(Vec::new()),
+            replaying: false,
         }
     }
 
+    pub fn from_replay(replay: &Replay) -> Self {
+        let mut app = Self::seeded(replay.settings, replay.seed);
+        app.recording = None;
+        app.replaying = true;
+        app
+    }
+
+    pub fn resume(snapshot: ClassicSnapshot) -> Self {
+        let mut app = Self::seeded(snapshot.settings, u64::from_le_bytes(snapshot.seed));
+        let now = app.sim_now;
+        app.rng = ChaCha12Rng::from_seed(snapshot.rng_seed);
+        app.rng.set_word_pos(snapshot.rng_word_pos as u128);
+        app.score = snapshot.score;
+        app.start_time = resume::ago(snapshot.elapsed, now);
+        app.show_intro = snapshot.intro_ago.is_some();
+        app.intro_start = resume::ago(snapshot.intro_ago.unwrap_or_default(), now);
+        app.next_spawn_time = resume::after(snapshot.next_spawn_in, now);
+        app.friendly_fire = snapshot.friendly_fire;
+        app.time_penalty = Duration::from_secs_f32(snapshot.time_penalty);
+        app.tally = snapshot.tally;
+        app.enemies = snapshot
+            .enemies
+            .into_iter()
+            .map(|enemy| Enemy {
+                x: enemy.x,
+                y: enemy.y,
+                texture_key: enemy.texture_key,
+                kind: enemy.kind,
+                state: match enemy.state {
+                    EnemyStateSnapshot::Alive => EnemyState::Alive,
+                    EnemyStateSnapshot::Dying(secs) => EnemyState::Dying(resume::ago(secs, now)),
+                },
+                spawn_time: resume::ago(enemy.age, now),
+            })
+            .collect();
+        app.recording = snapshot
+            .recording
+            .and_then(|frames| replay::unpack_frames(&frames));
+        app
+    }
+
+    pub fn snapshot(&self) -> Option<ClassicSnapshot> {
+        if self.game_over || self.replaying {
+            return None;
+        }
+        let now = self.sim_now;
+        Some(ClassicSnapshot {
+            settings: self.settings,
+            seed: self.seed.to_le_bytes(),
+            rng_seed: self.rng.get_seed(),
+            rng_word_pos: self.rng.get_word_pos() as u64,
+            score: self.score,
+            elapsed: resume::elapsed(self.start_time, now),
+            intro_ago: self
+                .show_intro
+                .then(|| resume::elapsed(self.intro_start, now)),
+            next_spawn_in: resume::remaining(self.next_spawn_time, now),
+            friendly_fire: self.friendly_fire,
+            time_penalty: self.time_penalty.as_secs_f32(),
+            tally: self.tally.clone(),
+            enemies: self
+                .enemies
+                .iter()
+                .map(|enemy| EnemySnapshot {
+                    x: enemy.x,
+                    y: enemy.y,
+                    texture_key: enemy.texture_key.clone(),
+                    kind: enemy.kind,
+                    state: match enemy.state {
+                        EnemyState::Alive => EnemyStateSnapshot::Alive,
+                        EnemyState::Dying(t0) => {
+                            EnemyStateSnapshot::Dying(resume::elapsed(t0, now))
+                        }
+                    },
+                    age: resume::elapsed(enemy.spawn_time, now),
+                })
+                .collect(),
+            recording: self.recording.as_deref().map(replay::pack_frames),
+        })
+    }
+
+    pub fn take_summary(&mut self) -> Option<RoundSummary> {
+        if !self.game_over || self.summary_taken || self.replaying {
+            return None;
+        }
+        self.summary_taken = true;
+        let elapsed = self.sim_now.duration_since(self.start_time) + self.time_penalty;
+        Some(RoundSummary {
+            mode: "Klasik".to_string(),
+            difficulty: self.settings.difficulty,
+            score: self.final_score(),
+            secs: elapsed.min(Duration::from_secs(20)).as_secs_f32(),
+            timed: false,
+            tally: self.tally.clone(),
+        })
+    }
+
+    pub fn take_achievements(&mut self) -> Vec<Achievement> {
+        self.achievements.take()
+    }
+
     pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
-        let now = Instant::now();
+        if !self.game_over && !self.replaying && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            return Some("menu".to_string());
+        }
+        let elapsed = Instant::now().duration_since(self.last_update);
+        let input = ctx.input(|i| FrameInput {
+            dt: Duration::from_micros(elapsed.as_micros().min(u32::MAX as u128) as u64),
+            pointer: i.pointer.interact_pos(),
+            click: i.pointer.primary_clicked(),
+            ..FrameInput::default()
+        });
+        self.step(&input, ctx.screen_rect());
+        self.draw(ctx)
+    }
+
+    pub fn step(&mut self, input: &FrameInput, field: egui::Rect) -> Vec<GameEvent> {
+        let was_over = self.game_over;
+        self.last_update += input.dt;
+        self.sim_now += input.dt;
+        let now = self.sim_now;
+        self.pointer = input.pointer.map(|pos| self.mutators.aim(pos, field));
+        if let Some(frames) = &mut self.recording {
+            frames.push(*input);
+        }
+
+        if self.show_intro {
+            if now.duration_since(self.intro_start) >= Duration::from_secs(4) {
+                self.start_time = now;
+                self.show_intro = false;
+                self.events.emit(GameEvent::RoundStarted);
+            }
+        } else if !self.game_over {
+            let elapsed = now.duration_since(self.start_time) + self.time_penalty;
+            if elapsed >= Duration::from_secs(20) {
+                self.game_over = true;
+                if self.tally.shots >= achievement::SHARPSHOOTER_SHOTS
+                    && self.tally.hits == self.tally.shots
+                    && !self.replaying
+                {
+                    self.achievements.achieve(Achievement::Sharpshooter);
+                }
+            } else {
+                if now >= self.next_spawn_time {
+                    self.spawn_enemy(elapsed);
+                    let spawn_delay = self
+                        .difficulty
+                        .spawn_delay(pace(self.mutators), &mut self.rng);
+                    self.next_spawn_time = now + spawn_delay;
+                }
+
+                let lifetime = self.difficulty.enemy_lifetime.div_f32(pace(self.mutators));
+                self.enemies.retain_mut(|enemy| match &mut enemy.state {
+                    EnemyState::Alive => now.duration_since(enemy.spawn_time) < lifetime,
+                    EnemyState::Dying(t0) => now.duration_since(*t0) < Duration::from_millis(500),
+                });
+
+                if input.click
+                    && let Some(pos) = self.pointer
+                {
+                    self.handle_shot(pos, now, egui::vec2(DISPLAY_WIDTH, DISPLAY_HEIGHT));
+                }
+            }
+        }
+
+        if !was_over && self.game_over {
+            self.events.emit(GameEvent::RoundEnded {
+                score: self.final_score(),
+            });
+            self.save_replay();
+        }
+        self.dispatch()
+    }
+
+    pub fn draw(&mut self, ctx: &egui::Context) -> Option<String> {
+        let now = self.sim_now;
         let mut signal = None;
 
         for name in [
@@ -65,6 +337,10 @@ impl ClassicApp {
             "assets/sprite/enemy-2.png",
             "assets/sprite/enemy_death-1.png",
             "assets/sprite/enemy_death-2.png",
+            "assets/sprite/civilian-1.png",
+            "assets/sprite/civilian-2.png",
+            "assets/sprite/civilian_death-1.png",
+            "assets/sprite/civilian_death-2.png",
         ] {
             if !self.textures.contains_key(name) {
                 let texture = load_image(ctx, name);
@@ -72,9 +348,6 @@ impl ClassicApp {
             }
         }
 
-        let display_width = 75.0;
-        let display_height = display_width * (55.0 / 35.0);
-
         egui::CentralPanel::default().show(ctx, |ui| {
             let elapsed_intro = now.duration_since(self.intro_start);
 
@@ -84,38 +357,55 @@ impl ClassicApp {
                     if self.restart_count == 0 {
                         if elapsed_intro < Duration::from_secs(2) {
                             ui.heading(RichText::new("Hazır mısın?").size(36.0));
-                        } else if elapsed_intro < Duration::from_secs(4) {
-                            ui.heading(RichText::new("Başla!").size(36.0));
                         } else {
-                            self.start_time = now;
-                            self.show_intro = false;
+                            ui.heading(RichText::new("Başla!").size(36.0));
                         }
                     } else {
                         if elapsed_intro < Duration::from_secs(2) {
                             ui.heading(RichText::new("Hazır mısın?").size(36.0));
-                        } else if elapsed_intro < Duration::from_secs(4) {
-                            ui.heading(RichText::new("Başla!").size(36.0));
                         } else {
-                            self.start_time = now;
-                            self.show_intro = false;
+                            ui.heading(RichText::new("Başla!").size(36.0));
                         }
                     }
+                    if !self.replaying {
+                        ui.add_space(20.0);
+                        ui.label(
+                            RichText::new("Esc: turu kaydet ve menüye dön")
+                                .size(16.0)
+                                .color(egui::Color32::GRAY),
+                        );
+                    }
                 });
                 return;
             }
 
-            let elapsed = now.duration_since(self.start_time);
-            if elapsed >= Duration::from_secs(20) {
-                self.game_over = true;
-            }
-
             if self.game_over {
                 ui.vertical_centered(|ui| {
                     ui.add_space(150.0);
                     ui.heading(RichText::new("Oyun Bitti!").size(32.0));
                     ui.add_space(20.0);
-                    ui.label(RichText::new(format!("Toplam Puan: {}", self.score)).size(24.0));
+                    ui.label(
+                        RichText::new(format!("Toplam Puan: {}", self.final_score())).size(24.0),
+                    );
+                    if !self.mutators.is_empty() {
+                        ui.label(
+                            RichText::new(self.mutators.summary())
+                                .size(18.0)
+                                .color(egui::Color32::from_rgb(200, 120, 0)),
+                        );
+                    }
+                    if self.missed_one_shot {
+                        ui.label(RichText::new("Tek atış kaçtı!").size(18.0));
+                    }
+                    ui.label(
+                        RichText::new(format!("Sivil Kayıp: {}", self.friendly_fire))
+                            .size(20.0)
+                            .color(egui::Color32::RED),
+                    );
                     ui.add_space(20.0);
+                    if self.replaying {
+                        return;
+                    }
 
                     if ui.button(RichText::new("Tekrar Oyna").size(20.0)).clicked() {
                         signal = Some("restart".to_string());
@@ -126,39 +416,56 @@ impl ClassicApp {
                     if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
                         signal = Some("menu".to_string());
                     }
+
+                    ui.add_space(10.0);
+
+                    if ui
+                        .button(RichText::new("Isı Haritası").size(20.0))
+                        .clicked()
+                    {
+                        signal = Some("heatmap".to_string());
+                    }
                 });
                 return;
             }
 
+            let elapsed = now.duration_since(self.start_time) + self.time_penalty;
             ui.label(RichText::new(format!("Süre: {}", 30 - elapsed.as_secs().min(30))).size(20.0));
-            ui.label(RichText::new(format!("Puan: {}", self.score)).size(20.0));
-
-            if now >= self.next_spawn_time {
-                self.spawn_enemy();
-                let spawn_delay = Duration::from_secs_f32(rand::thread_rng().gen_range(0.5..=1.0));
-                self.next_spawn_time = now + spawn_delay;
+            ui.label(RichText::new(format!("Puan: {}", self.final_score())).size(20.0));
+            if !self.mutators.is_empty() {
+                ui.label(
+                    RichText::new(self.mutators.names())
+                        .size(16.0)
+                        .color(egui::Color32::from_rgb(200, 120, 0)),
+                );
             }
 
-            self.enemies.retain_mut(|enemy| match &mut enemy.state {
-                EnemyState::Alive => now.duration_since(enemy.spawn_time) < Duration::from_secs(3),
-                EnemyState::Dying(t0) => now.duration_since(*t0) < Duration::from_millis(500),
-            });
-
             for enemy in &self.enemies {
+                if self.mutators.contains(Mutator::Invisible)
+                    && matches!(enemy.state, EnemyState::Alive)
+                    && now.duration_since(enemy.spawn_time).as_secs_f32() > FLASH_TIME
+                {
+                    continue;
+                }
                 let tex_key = match &enemy.state {
                     EnemyState::Alive => enemy.texture_key.clone(),
                     EnemyState::Dying(t0) => {
+                        let prefix = match enemy.kind {
+                            EnemyKind::Hostile => "enemy",
+                            EnemyKind::Civilian => "civilian",
+                        };
                         if now.duration_since(*t0) < Duration::from_millis(250) {
-                            "assets/sprite/enemy_death-1.png".to_string()
+                            format!("assets/sprite/{}_death-1.png", prefix)
                         } else {
-                            "assets/sprite/enemy_death-2.png".to_string()
+                            format!("assets/sprite/{}_death-2.png", prefix)
                         }
                     }
                 };
                 if let Some(texture) = self.textures.get(&tex_key) {
-                    let rect = egui::Rect::from_min_size(
-                        egui::pos2(enemy.x, enemy.y),
-                        egui::vec2(display_width, display_height),
+                    let rect = enemy.rect(
+                        now,
+                        self.mutators,
+                        egui::vec2(DISPLAY_WIDTH, DISPLAY_HEIGHT),
                     );
                     ui.painter().image(
                         texture.id(),
@@ -171,43 +478,10 @@ impl ClassicApp {
         });
 
         if !self.show_intro && !self.game_over {
-            ctx.input(|i| {
-                if i.pointer.primary_clicked() {
-                    self.play_sound("assets/sound/gunshot.mp3");
-                    if let Some(pos) = i.pointer.interact_pos() {
-                        for enemy in &mut self.enemies {
-                            if matches!(enemy.state, EnemyState::Alive) || matches!(enemy.state, EnemyState::Dying(_)){
-                                let rect = egui::Rect::from_min_size(
-                                    egui::pos2(enemy.x, enemy.y),
-                                    egui::vec2(display_width, display_height),
-                                );
-                                if rect.contains(pos) {
-                                    if let EnemyState::Alive = enemy.state {
-                                        enemy.state = EnemyState::Dying(Instant::now());
-                                    }
-
-                                    self.score += 1;
-
-                                    let mut rng = rand::thread_rng();
-                                    let chance: f64 = rng.r#gen();
-                                    if chance < 0.05 {
-                                        self.play_sound("assets/sound/enemy_death-special.mp3");
-                                    } else {
-                                        let index = rng.gen_range(1..=3);
-                                        let death_file =
-                                            format!("assets/sound/enemy_death-{}.wav", index);
-                                        self.play_sound(&death_file);
-                                    }
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-            });
-
-            ctx.output_mut(|o| o.cursor_icon = egui::CursorIcon::None);
-            if let Some(pos) = ctx.input(|i| i.pointer.hover_pos()) {
+            if !self.replaying {
+                ctx.output_mut(|o| o.cursor_icon = egui::CursorIcon::None);
+            }
+            if let Some(pos) = self.pointer {
                 let painter = ctx.layer_painter(egui::LayerId::new(
                     egui::Order::Foreground,
                     egui::Id::new("crosshair"),
@@ -250,34 +524,163 @@ impl ClassicApp {
         signal
     }
 
-    fn spawn_enemy(&mut self) {
-        let now = Instant::now();
-        let mut rng = rand::thread_rng();
+    fn save_replay(&mut self) {
+        let Some(frames) = self.recording.take() else {
+            return;
+        };
+        Replay::new(
+            ReplayKind::Classic,
+            self.seed,
+            self.settings,
+            self.final_score(),
+            frames,
+        )
+        .save();
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.sounds.set_muted(muted);
+    }
+    fn handle_shot(&mut self, pos: egui::Pos2, now: Instant, size: egui::Vec2) {
+        let mut hit = false;
+        for enemy in &mut self.enemies {
+            let rect = enemy.rect(now, self.mutators, size);
+            if !rect.contains(pos) {
+                continue;
+            }
+            hit = true;
+            let enemy_type = enemy.kind.enemy_type();
+            let was_alive = matches!(enemy.state, EnemyState::Alive);
+            if was_alive {
+                enemy.state = EnemyState::Dying(now);
+            }
+            self.events.emit(GameEvent::EnemyHit {
+                enemy_type,
+                pos: rect.center(),
+                killed: was_alive,
+            });
+            if was_alive {
+                self.events.emit(GameEvent::EnemyKilled {
+                    enemy_type,
+                    pos: rect.center(),
+                    cause: KillCause::Shot,
+                    reaction: (enemy.kind == EnemyKind::Hostile)
+                        .then(|| now.duration_since(enemy.spawn_time)),
+                    special: self.rng.gen_bool(0.05),
+                });
+            }
+            match (enemy.kind, was_alive) {
+                (EnemyKind::Civilian, true) => {
+                    self.events.emit(GameEvent::ScoreAwarded {
+                        points: -3,
+                        pos: rect.center(),
+                    });
+                    self.events.emit(GameEvent::TimeLost { seconds: 2 });
+                }
+                (EnemyKind::Civilian, false) => {}
+                (EnemyKind::Hostile, _) => self.events.emit(GameEvent::ScoreAwarded {
+                    points: 1,
+                    pos: rect.center(),
+                }),
+            }
+            break;
+        }
+        self.events.emit(GameEvent::ShotFired { pos, hit });
+        if !hit && self.mutators.contains(Mutator::OneShot) {
+            self.missed_one_shot = true;
+            self.game_over = true;
+        }
+    }
+
+    fn dispatch(&mut self) -> Vec<GameEvent> {
+        let events = self.events.drain();
+        for event in &events {
+            match *event {
+                GameEvent::ScoreAwarded { points, .. } => {
+                    self.score = (self.score as i64 + points).max(0) as u32;
+                }
+                GameEvent::TimeLost { seconds } => {
+                    self.time_penalty += Duration::from_secs(seconds as u64);
+                }
+                GameEvent::EnemyKilled {
+                    enemy_type: EnemyType::Civilian,
+                    ..
+                } => self.friendly_fire += 1,
+                _ => {}
+            }
+            self.tally.on_event(event);
+            self.sounds.on_event(event);
+            if !self.replaying {
+                self.achievements.on_event(event);
+            }
+        }
+        events
+    }
+
+    fn spawn_enemy(&mut self, elapsed: Duration) {
+        let now = self.sim_now;
+        let rng = &mut self.rng;
         let x = rng.gen_range(0.0..750.0);
         let y = rng.gen_range(0.0..500.0);
-        let texture_key = format!("assets/sprite/enemy-{}.png", rng.gen_range(1..=2));
+        let civilian_chance = if self.mutators.contains(Mutator::ElitesOnly) {
+            0.0
+        } else {
+            self.difficulty
+                .civilian_chance(elapsed.as_secs_f64() / 20.0)
+        };
+        let (kind, prefix) = if rng.gen_bool(civilian_chance) {
+            (EnemyKind::Civilian, "civilian")
+        } else {
+            (EnemyKind::Hostile, "enemy")
+        };
+        let texture_key = format!("assets/sprite/{}-{}.png", prefix, rng.gen_range(1..=2));
+        self.events.emit(GameEvent::EnemySpawned {
+            enemy_type: kind.enemy_type(),
+            pos: egui::pos2(x + 37.5, y + 58.9),
+        });
         self.enemies.push(Enemy {
             x,
             y,
             texture_key,
+            kind,
             state: EnemyState::Alive,
             spawn_time: now,
         });
     }
 
-    fn play_sound(&self, path: &str) {
-        if let Ok(file) = std::fs::File::open(path) {
-            let buffered = BufReader::new(file);
-            if let Ok(source) = Decoder::new(buffered) {
-                if let Ok(sink) = Sink::try_new(&self.audio_handle) {
-                    sink.append(source);
-                    sink.detach();
-                }
-            }
+    fn final_score(&self) -> u32 {
+        (self.score as f32 * self.mutators.score_factor()).round() as u32
+    }
+}
+
+impl EnemyKind {
+    fn enemy_type(self) -> EnemyType {
+        match self {
+            EnemyKind::Hostile => EnemyType::Normal,
+            EnemyKind::Civilian => EnemyType::Civilian,
         }
     }
 }
 
+impl Enemy {
+    fn rect(&self, now: Instant, mutators: MutatorSet, size: egui::Vec2) -> egui::Rect {
+        let t = now.duration_since(self.spawn_time).as_secs_f32();
+        let offset = mutators.float_offset(t, self.x);
+        mutators.target_rect(egui::Rect::from_min_size(
+            egui::pos2(self.x, self.y) + offset,
+            size,
+        ))
+    }
+}
+
+fn pace(mutators: MutatorSet) -> f32 {
+    if mutators.contains(Mutator::DoubleSpeed) {
+        DOUBLE_SPEED
+    } else {
+        1.0
+    }
+}
+
 fn load_image(ctx: &egui::Context, path: &str) -> TextureHandle {
     let img = ImageReader::open(path).unwrap().decode().unwrap();
     let rgba = img.to_rgba8();
@@ -286,3 +689,106 @@ fn load_image(ctx: &egui::Context, path: &str) -> TextureHandle {
         egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &rgba);
     ctx.load_texture(path, color_image, TextureOptions::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field() -> egui::Rect {
+        egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0))
+    }
+
+    fn frame(dt_ms: u64) -> FrameInput {
+        FrameInput {
+            dt: Duration::from_millis(dt_ms),
+            ..FrameInput::default()
+        }
+    }
+
+    fn click(x: f32, y: f32) -> FrameInput {
+        FrameInput {
+            pointer: Some(egui::pos2(x, y)),
+            click: true,
+            ..frame(1)
+        }
+    }
+
+    fn started(seed: u64) -> ClassicApp {
+        let mut app = ClassicApp::with_sounds(Settings::default(), seed, SoundBoard::silent());
+        app.recording = None;
+        let events = app.step(&frame(4000), field());
+        assert!(events.contains(&GameEvent::RoundStarted));
+        app.next_spawn_time = app.sim_now + Duration::from_secs(60);
+        app
+    }
+
+    fn add_enemy(app: &mut ClassicApp, kind: EnemyKind, x: f32, y: f32) {
+        app.enemies.push(Enemy {
+            x,
+            y,
+            texture_key: String::new(),
+            kind,
+            state: EnemyState::Alive,
+            spawn_time: app.sim_now,
+        });
+    }
+
+    #[test]
+    fn shot_on_hostile_scores() {
+        let mut app = started(7);
+        add_enemy(&mut app, EnemyKind::Hostile, 100.0, 100.0);
+
+        let events = app.step(&click(130.0, 150.0), field());
+
+        assert!(events.contains(&GameEvent::ShotFired {
+            pos: egui::pos2(130.0, 150.0),
+            hit: true,
+        }));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            GameEvent::EnemyKilled {
+                enemy_type: EnemyType::Normal,
+                cause: KillCause::Shot,
+                ..
+            }
+        )));
+        assert_eq!(app.score, 1);
+    }
+
+    #[test]
+    fn shot_on_civilian_costs_points_and_time() {
+        let mut app = started(7);
+        app.score = 5;
+        add_enemy(&mut app, EnemyKind::Civilian, 100.0, 100.0);
+
+        app.step(&click(130.0, 150.0), field());
+
+        assert_eq!(app.score, 2);
+        assert_eq!(app.friendly_fire, 1);
+        assert_eq!(app.time_penalty, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn same_seed_and_input_give_same_round() {
+        let play = |seed| {
+            let mut app = ClassicApp::with_sounds(Settings::default(), seed, SoundBoard::silent());
+            app.recording = None;
+            let mut events = Vec::new();
+            for tick in 0..600u32 {
+                let input = if tick % 5 == 0 {
+                    click(
+                        (tick * 37 % 750) as f32 + 30.0,
+                        (tick * 53 % 500) as f32 + 40.0,
+                    )
+                } else {
+                    frame(50)
+                };
+                events.extend(app.step(&input, field()));
+            }
+            assert!(app.game_over);
+            (events, app.final_score())
+        };
+
+        assert_eq!(play(11), play(11));
+    }
+}
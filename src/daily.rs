@@ -0,0 +1,184 @@
+use crate::mutator::{Mutator, MutatorSet};
+use crate::storage;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HISTORY_FILE: &str = "daily.toml";
+const DAILY_POOL: [Mutator; 3] = [Mutator::ElitesOnly, Mutator::NoHealth, Mutator::DoubleSpeed];
+const MUTATOR_COUNT: usize = 2;
+
+#[derive(Clone)]
+pub struct DailyChallenge {
+    pub date: String,
+    pub seed: u64,
+    pub mutators: MutatorSet,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct DailyHistory {
+    #[serde(default)]
+    pub entries: Vec<DailyEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DailyEntry {
+    pub date: String,
+    pub score: u32,
+    pub secs: f32,
+    pub mutators: MutatorSet,
+    #[serde(default)]
+    pub in_progress: bool,
+}
+
+pub struct DailyRun {
+    pub challenge: DailyChallenge,
+    pub official: bool,
+    pub history: DailyHistory,
+}
+
+pub fn civil_date(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+impl DailyChallenge {
+    pub fn today() -> DailyChallenge {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::for_day((secs / 86_400) as i64)
+    }
+
+    pub fn for_day(days: i64) -> DailyChallenge {
+        let (year, month, day) = civil_date(days);
+        let seed = (year as u64 * 10_000 + month as u64 * 100 + day as u64)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mutators = DAILY_POOL
+            .choose_multiple(&mut rng, MUTATOR_COUNT)
+            .copied()
+            .collect::<Vec<_>>()
+            .into();
+        DailyChallenge {
+            date: format!("{:04}-{:02}-{:02}", year, month, day),
+            seed,
+            mutators,
+        }
+    }
+}
+
+impl DailyHistory {
+    pub fn load() -> DailyHistory {
+        storage::load_toml(HISTORY_FILE)
+    }
+
+    pub fn entry(&self, date: &str) -> Option<&DailyEntry> {
+        self.entries.iter().find(|entry| entry.date == date)
+    }
+
+    pub fn begin(&mut self, challenge: &DailyChallenge) {
+        self.record(DailyEntry {
+            date: challenge.date.clone(),
+            score: 0,
+            secs: 0.0,
+            mutators: challenge.mutators,
+            in_progress: true,
+        });
+    }
+
+    pub fn record(&mut self, entry: DailyEntry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|existing| existing.date == entry.date)
+        {
+            Some(existing) if existing.in_progress => *existing = entry,
+            Some(_) => return,
+            None => self.entries.insert(0, entry),
+        }
+        storage::save_toml(HISTORY_FILE, self);
+    }
+}
+
+impl DailyRun {
+    pub fn start() -> DailyRun {
+        let challenge = DailyChallenge::today();
+        let history = DailyHistory::load();
+        DailyRun {
+            official: history.entry(&challenge.date).is_none(),
+            challenge,
+            history,
+        }
+    }
+
+    pub fn resume(date: &str, official: bool) -> DailyRun {
+        Self::resumed(
+            DailyChallenge::today(),
+            DailyHistory::load(),
+            date,
+            official,
+        )
+    }
+
+    fn resumed(
+        challenge: DailyChallenge,
+        history: DailyHistory,
+        date: &str,
+        official: bool,
+    ) -> DailyRun {
+        let in_progress = history.entry(date).is_some_and(|entry| entry.in_progress);
+        DailyRun {
+            official: official && in_progress && challenge.date == date,
+            challenge,
+            history,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(in_progress: bool) -> DailyHistory {
+        let challenge = DailyChallenge::for_day(20_000);
+        DailyHistory {
+            entries: vec![DailyEntry {
+                date: challenge.date,
+                score: 0,
+                secs: 0.0,
+                mutators: challenge.mutators,
+                in_progress,
+            }],
+        }
+    }
+
+    #[test]
+    fn resumed_official_run_stays_official() {
+        let challenge = DailyChallenge::for_day(20_000);
+        let date = challenge.date.clone();
+        let run = DailyRun::resumed(challenge, history(true), &date, true);
+        assert!(run.official);
+    }
+
+    #[test]
+    fn resumed_run_loses_official_status_when_not_claimed() {
+        let challenge = DailyChallenge::for_day(20_000);
+        let date = challenge.date.clone();
+        assert!(!DailyRun::resumed(challenge.clone(), history(true), &date, false).official);
+        assert!(!DailyRun::resumed(challenge.clone(), history(false), &date, true).official);
+        assert!(!DailyRun::resumed(challenge, DailyHistory::default(), &date, true).official);
+        let tomorrow = DailyChallenge::for_day(20_001);
+        assert!(!DailyRun::resumed(tomorrow, history(true), &date, true).official);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::mutator::MutatorSet;
+use crate::storage;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SETTINGS_FILE: &str = "settings.toml";
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+pub const ALL_DIFFICULTIES: [Difficulty; 4] = [
+    Difficulty::Easy,
+    Difficulty::Normal,
+    Difficulty::Hard,
+    Difficulty::Insane,
+];
+
+#[derive(Clone, Copy)]
+pub struct DifficultyParams {
+    pub spawn_interval: (f32, f32),
+    pub enemy_lifetime: Duration,
+    pub fire_delay: (f32, f32),
+    pub elite_hitpoints: u32,
+    pub supply_tier: usize,
+    pub civilian_share: (f64, f64),
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub director: bool,
+    #[serde(default)]
+    pub mutators: MutatorSet,
+    #[serde(default)]
+    pub ghost: bool,
+    #[serde(default)]
+    pub reduced_motion: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Director {
+    accuracy: f32,
+    pace: f32,
+}
+
+impl Difficulty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Kolay",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Zor",
+            Difficulty::Insane => "Çılgın",
+        }
+    }
+
+    pub fn params(&self) -> DifficultyParams {
+        match self {
+            Difficulty::Easy => DifficultyParams {
+                spawn_interval: (0.8, 1.4),
+                enemy_lifetime: Duration::from_secs(4),
+                fire_delay: (1.0, 1.6),
+                elite_hitpoints: 2,
+                supply_tier: 0,
+                civilian_share: (0.05, 0.15),
+            },
+            Difficulty::Normal => DifficultyParams {
+                spawn_interval: (0.5, 1.0),
+                enemy_lifetime: Duration::from_secs(3),
+                fire_delay: (0.7, 1.2),
+                elite_hitpoints: 3,
+                supply_tier: 0,
+                civilian_share: (0.1, 0.3),
+            },
+            Difficulty::Hard => DifficultyParams {
+                spawn_interval: (0.35, 0.75),
+                enemy_lifetime: Duration::from_millis(2400),
+                fire_delay: (0.5, 0.9),
+                elite_hitpoints: 4,
+                supply_tier: 1,
+                civilian_share: (0.15, 0.35),
+            },
+            Difficulty::Insane => DifficultyParams {
+                spawn_interval: (0.25, 0.5),
+                enemy_lifetime: Duration::from_millis(1800),
+                fire_delay: (0.35, 0.65),
+                elite_hitpoints: 5,
+                supply_tier: 2,
+                civilian_share: (0.2, 0.4),
+            },
+        }
+    }
+}
+
+impl DifficultyParams {
+    pub fn spawn_delay(&self, pace: f32, rng: &mut impl Rng) -> Duration {
+        let (min, max) = self.spawn_interval;
+        Duration::from_secs_f32(rng.gen_range(min..=max) / pace)
+    }
+
+    pub fn fire_delay(&self, pace: f32, rng: &mut impl Rng) -> Duration {
+        let (min, max) = self.fire_delay;
+        Duration::from_secs_f32(rng.gen_range(min..=max) / pace)
+    }
+
+    pub fn civilian_chance(&self, progress: f64) -> f64 {
+        let (min, max) = self.civilian_share;
+        min + (max - min) * progress.clamp(0.0, 1.0)
+    }
+}
+
+impl Settings {
+    pub fn load() -> Settings {
+        storage::load_toml(SETTINGS_FILE)
+    }
+
+    pub fn save(&self) {
+        storage::save_toml(SETTINGS_FILE, self);
+    }
+}
+
+impl Director {
+    pub fn new() -> Self {
+        Director {
+            accuracy: 0.5,
+            pace: 1.0,
+        }
+    }
+
+    pub fn record_shot(&mut self, hit: bool) {
+        self.accuracy = self.accuracy * 0.9 + if hit { 0.1 } else { 0.0 };
+    }
+
+    pub fn update(&mut self, delta: Duration, time_ratio: f32) {
+        let target = (1.0 + (self.accuracy - 0.5) + (time_ratio - 0.5) * 0.6).clamp(0.6, 1.6);
+        let step = delta.as_secs_f32() * 0.2;
+        self.pace += (target - self.pace).clamp(-step, step);
+    }
+
+    pub fn pace(&self) -> f32 {
+        self.pace
+    }
+}
@@ -0,0 +1,84 @@
+use crate::advanced::EnemyType;
+use crate::supply::SupplyBoxType;
+use eframe::egui;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KillCause {
+    Shot,
+    Explosion,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    RoundStarted,
+    ShotFired {
+        pos: egui::Pos2,
+        hit: bool,
+    },
+    EnemySpawned {
+        enemy_type: EnemyType,
+        pos: egui::Pos2,
+    },
+    EnemyHit {
+        enemy_type: EnemyType,
+        pos: egui::Pos2,
+        killed: bool,
+    },
+    EnemyKilled {
+        enemy_type: EnemyType,
+        pos: egui::Pos2,
+        cause: KillCause,
+        reaction: Option<Duration>,
+        special: bool,
+    },
+    ScoreAwarded {
+        points: i64,
+        pos: egui::Pos2,
+    },
+    EnemyFired {
+        enemy_type: EnemyType,
+        damage: i64,
+        blocked: bool,
+    },
+    TimeLost {
+        seconds: i64,
+    },
+    BossWounded,
+    BossDefeated,
+    SupplyDamaged {
+        kind: SupplyBoxType,
+    },
+    SupplyCollected {
+        kind: SupplyBoxType,
+    },
+    TntExploded {
+        center: egui::Pos2,
+        radius: f32,
+        chain: u32,
+        kills: u32,
+        special: bool,
+    },
+    RoundEnded {
+        score: u32,
+    },
+}
+
+pub trait Subscriber {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+#[derive(Default)]
+pub struct EventBus {
+    queue: Vec<GameEvent>,
+}
+
+impl EventBus {
+    pub fn emit(&mut self, event: GameEvent) {
+        self.queue.push(event);
+    }
+
+    pub fn drain(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.queue)
+    }
+}
@@ -0,0 +1,252 @@
+use crate::storage;
+use egui::{Color32, Context, RichText};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const HEATMAP_FILE: &str = "heatmap.toml";
+const FIELD_WIDTH: u32 = 800;
+const FIELD_HEIGHT: u32 = 600;
+const CELL: u32 = 20;
+const COLS: usize = (FIELD_WIDTH / CELL) as usize;
+const ROWS: usize = (FIELD_HEIGHT / CELL) as usize;
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct Heatmap {
+    #[serde(default)]
+    hits: Vec<u32>,
+    #[serde(default)]
+    misses: Vec<u32>,
+    #[serde(default)]
+    spawns: Vec<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HeatLayer {
+    Hits,
+    Misses,
+    Spawns,
+}
+
+const ALL_LAYERS: [HeatLayer; 3] = [HeatLayer::Hits, HeatLayer::Misses, HeatLayer::Spawns];
+
+pub struct HeatmapScreen {
+    heatmap: Heatmap,
+    layer: HeatLayer,
+    message: Option<String>,
+}
+
+impl HeatLayer {
+    fn name(&self) -> &'static str {
+        match self {
+            HeatLayer::Hits => "İsabetler",
+            HeatLayer::Misses => "Iskalar",
+            HeatLayer::Spawns => "Düşman Doğuşları",
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            HeatLayer::Hits => "heatmap_hits.png",
+            HeatLayer::Misses => "heatmap_misses.png",
+            HeatLayer::Spawns => "heatmap_spawns.png",
+        }
+    }
+}
+
+impl Heatmap {
+    pub fn load() -> Heatmap {
+        storage::load_toml(HEATMAP_FILE)
+    }
+
+    pub fn save(&self) {
+        storage::save_toml(HEATMAP_FILE, self);
+    }
+
+    fn cells(&self, layer: HeatLayer) -> &Vec<u32> {
+        match layer {
+            HeatLayer::Hits => &self.hits,
+            HeatLayer::Misses => &self.misses,
+            HeatLayer::Spawns => &self.spawns,
+        }
+    }
+
+    fn cells_mut(&mut self, layer: HeatLayer) -> &mut Vec<u32> {
+        let cells = match layer {
+            HeatLayer::Hits => &mut self.hits,
+            HeatLayer::Misses => &mut self.misses,
+            HeatLayer::Spawns => &mut self.spawns,
+        };
+        cells.resize(COLS * ROWS, 0);
+        cells
+    }
+
+    pub fn add(&mut self, layer: HeatLayer, pos: egui::Pos2) {
+        let col = (pos.x.max(0.0) as usize / CELL as usize).min(COLS - 1);
+        let row = (pos.y.max(0.0) as usize / CELL as usize).min(ROWS - 1);
+        self.cells_mut(layer)[row * COLS + col] += 1;
+    }
+
+    pub fn shot(&mut self, pos: egui::Pos2, hit: bool) {
+        let layer = if hit {
+            HeatLayer::Hits
+        } else {
+            HeatLayer::Misses
+        };
+        self.add(layer, pos);
+    }
+
+    pub fn merge(&mut self, other: &Heatmap) {
+        for layer in ALL_LAYERS {
+            let added = other.cells(layer).clone();
+            for (cell, count) in self.cells_mut(layer).iter_mut().zip(added) {
+                *cell += count;
+            }
+        }
+    }
+
+    fn total(&self, layer: HeatLayer) -> u32 {
+        self.cells(layer).iter().sum()
+    }
+
+    fn intensities(&self, layer: HeatLayer) -> Vec<f32> {
+        let cells = self.cells(layer);
+        let count = |col: isize, row: isize| -> f32 {
+            if col < 0 || row < 0 || col >= COLS as isize || row >= ROWS as isize {
+                return 0.0;
+            }
+            cells
+                .get(row as usize * COLS + col as usize)
+                .copied()
+                .unwrap_or(0) as f32
+        };
+        let mut smoothed = vec![0.0; COLS * ROWS];
+        for row in 0..ROWS as isize {
+            for col in 0..COLS as isize {
+                let mut sum = count(col, row) * 4.0;
+                for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    sum += count(col + dx, row + dy) * 2.0;
+                }
+                for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+                    sum += count(col + dx, row + dy);
+                }
+                smoothed[row as usize * COLS + col as usize] = sum;
+            }
+        }
+        let max = smoothed.iter().copied().fold(0.0, f32::max);
+        if max > 0.0 {
+            for value in &mut smoothed {
+                *value /= max;
+            }
+        }
+        smoothed
+    }
+
+    pub fn export_png(&self, layer: HeatLayer) -> Result<PathBuf, String> {
+        let intensities = self.intensities(layer);
+        let image = RgbaImage::from_fn(FIELD_WIDTH, FIELD_HEIGHT, |x, y| {
+            let t = intensities[(y / CELL) as usize * COLS + (x / CELL) as usize];
+            let heat = heat_color(t);
+            let alpha = heat.a() as f32 / 255.0;
+            let blend = |channel: u8| (channel as f32 * alpha + 255.0 * (1.0 - alpha)) as u8;
+            Rgba([blend(heat.r()), blend(heat.g()), blend(heat.b()), 255])
+        });
+        std::fs::create_dir_all(storage::save_dir()).map_err(|e| e.to_string())?;
+        let path = storage::save_path(layer.file_name());
+        image.save(&path).map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+}
+
+fn heat_color(t: f32) -> Color32 {
+    if t <= 0.0 {
+        return Color32::TRANSPARENT;
+    }
+    let stops = [
+        (0.0, [0.0, 0.0, 255.0]),
+        (0.35, [0.0, 200.0, 0.0]),
+        (0.7, [255.0, 220.0, 0.0]),
+        (1.0, [230.0, 0.0, 0.0]),
+    ];
+    let t = t.clamp(0.0, 1.0);
+    let index = stops
+        .windows(2)
+        .position(|pair| t <= pair[1].0)
+        .unwrap_or(stops.len() - 2);
+    let (from, to) = (stops[index], stops[index + 1]);
+    let k = (t - from.0) / (to.0 - from.0);
+    let channel = |i: usize| (from.1[i] + (to.1[i] - from.1[i]) * k) as u8;
+    let alpha = (60.0 + 180.0 * t) as u8;
+    Color32::from_rgba_unmultiplied(channel(0), channel(1), channel(2), alpha)
+}
+
+impl HeatmapScreen {
+    pub fn new() -> Self {
+        HeatmapScreen {
+            heatmap: Heatmap::load(),
+            layer: HeatLayer::Misses,
+            message: None,
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context) -> Option<String> {
+        let mut signal = None;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let field = ctx.screen_rect();
+            let scale = egui::vec2(
+                field.width() / FIELD_WIDTH as f32,
+                field.height() / FIELD_HEIGHT as f32,
+            );
+            let painter = ui.painter();
+            for (index, t) in self.heatmap.intensities(self.layer).iter().enumerate() {
+                if *t <= 0.0 {
+                    continue;
+                }
+                let min = egui::pos2(
+                    ((index % COLS) as u32 * CELL) as f32,
+                    ((index / COLS) as u32 * CELL) as f32,
+                );
+                let rect = egui::Rect::from_min_size(
+                    field.min + min.to_vec2() * scale,
+                    egui::vec2(CELL as f32, CELL as f32) * scale,
+                );
+                painter.rect_filled(rect, 0.0, heat_color(*t));
+            }
+
+            ui.horizontal(|ui| {
+                for layer in ALL_LAYERS {
+                    let label = format!("{} ({})", layer.name(), self.heatmap.total(layer));
+                    if ui
+                        .selectable_label(self.layer == layer, RichText::new(label).size(16.0))
+                        .clicked()
+                    {
+                        self.layer = layer;
+                        self.message = None;
+                    }
+                }
+                ui.add_space(20.0);
+                if ui.button(RichText::new("PNG Kaydet").size(16.0)).clicked() {
+                    self.message = Some(match self.heatmap.export_png(self.layer) {
+                        Ok(path) => format!("Kaydedildi: {}", path.display()),
+                        Err(err) => format!("Kaydedilemedi: {}", err),
+                    });
+                }
+                if ui.button(RichText::new("Menüye Dön").size(16.0)).clicked() {
+                    signal = Some("menu".to_string());
+                }
+            });
+            if let Some(message) = &self.message {
+                ui.label(RichText::new(message).color(Color32::GRAY));
+            }
+            if self.heatmap.total(self.layer) == 0 {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(220.0);
+                    ui.label(RichText::new("Henüz veri yok, önce birkaç tur oyna.").size(18.0));
+                });
+            }
+        });
+
+        signal
+    }
+}
@@ -0,0 +1,348 @@
+use eframe::egui;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const LEVEL_DIR: &str = "assets/levels";
+
+#[derive(Deserialize, Clone)]
+pub struct Level {
+    pub name: String,
+    #[serde(default = "default_start_time")]
+    pub start_time: i64,
+    #[serde(default)]
+    pub objective: Option<Objective>,
+    pub waves: Vec<Wave>,
+    #[serde(skip)]
+    pub source: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Objective {
+    Score { stars: [u32; 3] },
+    Survive { stars: [u32; 3] },
+    Flawless { stars: [u32; 3] },
+}
+
+pub struct RoundStats {
+    pub score: u32,
+    pub survived_secs: u32,
+    pub best_streak: u32,
+    pub completed: bool,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Wave {
+    pub at: f32,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub spawns: Vec<SpawnEvent>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SpawnEvent {
+    pub at: f32,
+    pub kind: SpawnKind,
+    #[serde(default = "default_count")]
+    pub count: u32,
+    #[serde(default)]
+    pub interval: f32,
+    #[serde(default)]
+    pub position: Option<[f32; 2]>,
+    #[serde(default)]
+    pub zone: Option<Zone>,
+    #[serde(default)]
+    pub path: MovePath,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpawnKind {
+    Enemy,
+    Elite,
+    Civilian,
+    Health,
+    Tnt,
+    #[serde(rename = "slowmo")]
+    SlowMotion,
+    Freeze,
+    #[serde(rename = "double")]
+    DoublePoints,
+    Ammo,
+    Shield,
+    Supply,
+    Boss,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Zone {
+    pub x: [f32; 2],
+    pub y: [f32; 2],
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MovePath {
+    #[default]
+    Static,
+    Linear {
+        velocity: [f32; 2],
+    },
+    Sine {
+        amplitude: [f32; 2],
+        period: f32,
+    },
+    Waypoints {
+        points: Vec<[f32; 2]>,
+        speed: f32,
+    },
+}
+
+#[derive(Clone)]
+pub struct ScheduledSpawn {
+    pub at: f32,
+    pub wave: usize,
+    pub kind: SpawnKind,
+    pub position: Option<[f32; 2]>,
+    pub zone: Option<Zone>,
+    pub path: MovePath,
+}
+
+pub struct LevelScript {
+    pub level: Level,
+    timeline: Vec<ScheduledSpawn>,
+    cursor: usize,
+}
+
+fn default_start_time() -> i64 {
+    30
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+impl Level {
+    pub fn load(path: &Path) -> Result<Level, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("{} okunamadı: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("{} çözümlenemedi: {}", path.display(), e))
+    }
+
+    pub fn parse(text: &str) -> Result<Level, String> {
+        let mut level: Level = toml::from_str(text).map_err(|e| e.to_string())?;
+        level.source = text.to_string();
+        Ok(level)
+    }
+
+    pub fn list() -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(LEVEL_DIR)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        paths
+    }
+}
+
+impl Objective {
+    pub fn stars(&self, stats: &RoundStats) -> u8 {
+        let (value, thresholds) = match self {
+            Objective::Survive { stars } => (stats.survived_secs, stars),
+            _ if !stats.completed => return 0,
+            Objective::Score { stars } => (stats.score, stars),
+            Objective::Flawless { stars } => (stats.best_streak, stars),
+        };
+        thresholds.iter().filter(|&&t| value >= t).count() as u8
+    }
+
+    pub fn survive_goal(&self) -> Option<u32> {
+        match self {
+            Objective::Survive { stars } => stars.iter().copied().max(),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::Score { stars } => format!("Hedef: {} puan", stars[0]),
+            Objective::Survive { stars } => format!("Hedef: {} saniye hayatta kal", stars[0]),
+            Objective::Flawless { stars } => {
+                format!("Hedef: ıskalamadan {} vuruş", stars[0])
+            }
+        }
+    }
+}
+
+impl LevelScript {
+    pub fn new(level: Level) -> Self {
+        let mut timeline = Vec::new();
+        for (wave_index, wave) in level.waves.iter().enumerate() {
+            for spawn in &wave.spawns {
+                for i in 0..spawn.count {
+                    timeline.push(ScheduledSpawn {
+                        at: wave.at + spawn.at + spawn.interval * i as f32,
+                        wave: wave_index,
+                        kind: spawn.kind,
+                        position: spawn.position,
+                        zone: spawn.zone,
+                        path: spawn.path.clone(),
+                    });
+                }
+            }
+        }
+        timeline.sort_by(|a, b| a.at.total_cmp(&b.at));
+
+        LevelScript {
+            level,
+            timeline,
+            cursor: 0,
+        }
+    }
+
+    pub fn resume(level: Level, cursor: usize) -> Self {
+        let mut script = LevelScript::new(level);
+        script.cursor = cursor.min(script.timeline.len());
+        script
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn due(&mut self, elapsed: f32) -> Vec<ScheduledSpawn> {
+        let start = self.cursor;
+        while self.cursor < self.timeline.len() && self.timeline[self.cursor].at <= elapsed {
+            self.cursor += 1;
+        }
+        self.timeline[start..self.cursor].to_vec()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.timeline.len()
+    }
+
+    pub fn current_wave(&self) -> Option<&Wave> {
+        let index = self.timeline[..self.cursor].last()?.wave;
+        self.level.waves.get(index)
+    }
+}
+
+impl ScheduledSpawn {
+    pub fn pick_position(&self, max_x: f32, max_y: f32, rng: &mut impl Rng) -> (f32, f32) {
+        if let Some([x, y]) = self.position {
+            return (x, y);
+        }
+        let zone = self.zone.unwrap_or(Zone {
+            x: [0.0, max_x],
+            y: [0.0, max_y],
+        });
+        let x = rng.gen_range(zone.x[0]..=zone.x[1].max(zone.x[0]));
+        let y = rng.gen_range(zone.y[0]..=zone.y[1].max(zone.y[0]));
+        (x.clamp(0.0, max_x), y.clamp(0.0, max_y))
+    }
+}
+
+impl MovePath {
+    pub fn position(&self, origin: egui::Pos2, t: f32, max: egui::Pos2) -> egui::Pos2 {
+        match self {
+            MovePath::Static => origin,
+            MovePath::Linear { velocity } => egui::pos2(
+                bounce(origin.x + velocity[0] * t, max.x),
+                bounce(origin.y + velocity[1] * t, max.y),
+            ),
+            MovePath::Sine { amplitude, period } => {
+                let phase = (t / period.max(0.01)) * std::f32::consts::TAU;
+                egui::pos2(
+                    (origin.x + amplitude[0] * phase.sin()).clamp(0.0, max.x),
+                    (origin.y + amplitude[1] * phase.cos()).clamp(0.0, max.y),
+                )
+            }
+            MovePath::Waypoints { points, speed } => {
+                let mut route = vec![origin];
+                route.extend(points.iter().map(|[x, y]| egui::pos2(*x, *y)));
+                if route.len() < 2 {
+                    return origin;
+                }
+                let lengths: Vec<f32> = route
+                    .iter()
+                    .zip(route.iter().cycle().skip(1))
+                    .map(|(a, b)| a.distance(*b))
+                    .collect();
+                let total: f32 = lengths.iter().sum();
+                if total <= 0.0 {
+                    return origin;
+                }
+                let mut travelled = (t * speed) % total;
+                for (i, length) in lengths.iter().enumerate() {
+                    if travelled <= *length {
+                        let from = route[i];
+                        let to = route[(i + 1) % route.len()];
+                        return from + (to - from) * (travelled / length.max(0.001));
+                    }
+                    travelled -= length;
+                }
+                origin
+            }
+        }
+    }
+}
+
+fn bounce(value: f32, max: f32) -> f32 {
+    if max <= 0.0 {
+        return 0.0;
+    }
+    let span = max * 2.0;
+    let folded = value.rem_euclid(span);
+    if folded > max { span - folded } else { folded }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(score: u32, survived_secs: u32, best_streak: u32, completed: bool) -> RoundStats {
+        RoundStats {
+            score,
+            survived_secs,
+            best_streak,
+            completed,
+        }
+    }
+
+    #[test]
+    fn score_objective_rates_completed_runs_by_score() {
+        let objective = Objective::Score {
+            stars: [10, 20, 30],
+        };
+        assert_eq!(objective.stars(&stats(5, 60, 0, true)), 0);
+        assert_eq!(objective.stars(&stats(20, 0, 0, true)), 2);
+        assert_eq!(objective.stars(&stats(45, 0, 0, true)), 3);
+        assert_eq!(objective.stars(&stats(45, 0, 0, false)), 0);
+    }
+
+    #[test]
+    fn survive_objective_rates_time_survived() {
+        let objective = Objective::Survive {
+            stars: [20, 30, 40],
+        };
+        assert_eq!(objective.stars(&stats(0, 19, 0, false)), 0);
+        assert_eq!(objective.stars(&stats(0, 25, 0, false)), 1);
+        assert_eq!(objective.stars(&stats(0, 30, 0, true)), 2);
+        assert_eq!(objective.stars(&stats(0, 41, 0, false)), 3);
+    }
+
+    #[test]
+    fn flawless_objective_rates_completed_runs_by_streak() {
+        let objective = Objective::Flawless { stars: [5, 10, 15] };
+        assert_eq!(objective.stars(&stats(0, 0, 4, true)), 0);
+        assert_eq!(objective.stars(&stats(0, 0, 12, true)), 2);
+        assert_eq!(objective.stars(&stats(0, 0, 15, true)), 3);
+        assert_eq!(objective.stars(&stats(0, 0, 15, false)), 0);
+    }
+}
@@ -1,65 +1,248 @@
 #![windows_subsystem = "windows"]
 
+mod achievement;
 mod advanced;
+mod audio;
+mod boss;
+mod campaign;
 mod classic;
+mod daily;
+mod difficulty;
+mod event;
+mod heatmap;
+mod level;
 mod menu;
+mod music;
+mod mutator;
+mod particle;
+mod profile;
+mod replay;
+mod resume;
+mod rhythm;
+mod stats;
+mod storage;
+mod supply;
+mod survival;
+mod time_attack;
+mod trainer;
+mod upgrade;
 
 use eframe::{App, Frame, NativeOptions, egui};
 use egui::IconData;
 use image::io::Reader as ImageReader;
+use resume::SavedRound;
 
 enum Mode {
     Menu,
     Classic,
     Advanced,
-    Wip(&'static str),
+    Campaign,
+    TimeAttack,
+    Trainer,
+    Rhythm,
+    Replay,
+    Profiles,
+    Stats,
+    Heatmap,
+    Achievements,
+    Wip(String),
 }
 
 pub struct PoligonApp {
     mode: Mode,
     classic_state: Option<classic::ClassicApp>,
     advanced_state: Option<advanced::AdvancedApp>,
+    time_attack_state: Option<time_attack::TimeAttackApp>,
+    trainer_state: Option<trainer::TrainerApp>,
+    rhythm_state: Option<rhythm::RhythmApp>,
+    replay_state: Option<replay::ReplayViewer>,
+    profile_screen: Option<profile::ProfileScreen>,
+    stats_state: Option<stats::StatsScreen>,
+    heatmap_state: Option<heatmap::HeatmapScreen>,
+    profiles: profile::Profiles,
+    achievements: achievement::AchievementBook,
+    toasts: achievement::Toasts,
+    campaign: campaign::Campaign,
+    progress: campaign::CampaignProgress,
+    campaign_level: Option<usize>,
+    menu_info: Option<menu::MenuInfo>,
+    settings: difficulty::Settings,
 }
 
 impl Default for PoligonApp {
     fn default() -> Self {
+        let profiles = profile::Profiles::load();
         Self {
             mode: Mode::Menu,
             classic_state: None,
             advanced_state: None,
+            time_attack_state: None,
+            trainer_state: None,
+            rhythm_state: None,
+            replay_state: None,
+            profile_screen: None,
+            stats_state: None,
+            heatmap_state: None,
+            profiles,
+            achievements: achievement::AchievementBook::load(),
+            toasts: achievement::Toasts::default(),
+            campaign: campaign::Campaign::load(),
+            progress: campaign::CampaignProgress::load(),
+            campaign_level: None,
+            menu_info: None,
+            settings: difficulty::Settings::load(),
+        }
+    }
+}
+
+impl PoligonApp {
+    fn suspend_round(&self) {
+        let round = match self.mode {
+            Mode::Classic => self
+                .classic_state
+                .as_ref()
+                .and_then(|app| app.snapshot())
+                .map(|round| SavedRound::Classic(Box::new(round))),
+            Mode::Advanced => self
+                .advanced_state
+                .as_ref()
+                .and_then(|app| app.snapshot())
+                .map(|round| SavedRound::Advanced {
+                    campaign_level: self.campaign_level,
+                    round: Box::new(round),
+                }),
+            _ => None,
+        };
+        if let Some(round) = round {
+            round.save();
+        }
+    }
+
+    fn resume_round(&mut self) {
+        match SavedRound::take() {
+            Some(SavedRound::Classic(round)) => {
+                self.mode = Mode::Classic;
+                self.classic_state = Some(classic::ClassicApp::resume(*round));
+            }
+            Some(SavedRound::Advanced {
+                campaign_level,
+                round,
+            }) => match advanced::AdvancedApp::resume(*round) {
+                Ok(app) => {
+                    self.mode = Mode::Advanced;
+                    self.campaign_level = campaign_level;
+                    self.advanced_state = Some(app);
+                }
+                Err(err) => self.mode = Mode::Wip(err),
+            },
+            None => self.mode = Mode::Wip("Kayıtlı tur okunamadı".to_string()),
         }
     }
 }
 
 impl App for PoligonApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut Frame) {
+        if ctx.input(|i| i.viewport().close_requested()) {
+            self.suspend_round();
+        }
         match &mut self.mode {
             Mode::Menu => {
-                if let Some(result) = menu::draw_menu(ctx) {
+                let info = self.menu_info.get_or_insert_with(menu::MenuInfo::load);
+                if let Some(result) =
+                    menu::draw_menu(ctx, &mut self.settings, self.profiles.active_name(), info)
+                {
                     match result.as_str() {
                         "classic" => {
                             self.mode = Mode::Classic;
-                            self.classic_state = Some(classic::ClassicApp::new());
+                            self.classic_state = Some(classic::ClassicApp::new(self.settings));
                         }
                         "advanced" => {
                             self.mode = Mode::Advanced;
-                            self.advanced_state = Some(advanced::AdvancedApp::new());
+                            self.advanced_state = Some(advanced::AdvancedApp::new(self.settings));
+                        }
+                        "survival" => {
+                            self.mode = Mode::Advanced;
+                            self.advanced_state =
+                                Some(advanced::AdvancedApp::survival(self.settings));
+                        }
+                        "daily" => {
+                            self.mode = Mode::Advanced;
+                            self.advanced_state = Some(advanced::AdvancedApp::daily(self.settings));
+                        }
+                        "time_attack" => {
+                            self.mode = Mode::TimeAttack;
+                            self.time_attack_state = Some(time_attack::TimeAttackApp::new());
+                        }
+                        "trainer" => {
+                            self.mode = Mode::Trainer;
+                            self.trainer_state = Some(trainer::TrainerApp::new());
+                        }
+                        "rhythm" => {
+                            self.mode = Mode::Rhythm;
+                            self.rhythm_state = Some(rhythm::RhythmApp::new());
+                        }
+                        "replays" => {
+                            self.mode = Mode::Replay;
+                            self.replay_state = Some(replay::ReplayViewer::new());
+                        }
+                        "resume" => self.resume_round(),
+                        "heatmap" => {
+                            self.mode = Mode::Heatmap;
+                            self.heatmap_state = Some(heatmap::HeatmapScreen::new());
+                        }
+                        "stats" => {
+                            self.mode = Mode::Stats;
+                            self.stats_state = Some(stats::StatsScreen::new());
+                        }
+                        "profiles" => {
+                            self.mode = Mode::Profiles;
+                            self.profile_screen = Some(profile::ProfileScreen::new());
+                        }
+                        "achievements" => self.mode = Mode::Achievements,
+                        "campaign" => self.mode = Mode::Campaign,
+                        "wip_bonus" => {
+                            self.mode = Mode::Wip("Bonus mod çok yakında...".to_string())
+                        }
+                        other => {
+                            if let Some(path) = other.strip_prefix("level:") {
+                                match level::Level::load(std::path::Path::new(path)) {
+                                    Ok(level) => {
+                                        self.mode = Mode::Advanced;
+                                        self.advanced_state = Some(
+                                            advanced::AdvancedApp::with_level(level, self.settings),
+                                        );
+                                    }
+                                    Err(err) => self.mode = Mode::Wip(err),
+                                }
+                            }
                         }
-                        "wip_bonus" => self.mode = Mode::Wip("Bonus mod çok yakında..."),
-                        _ => {}
+                    }
+                    if !matches!(self.mode, Mode::Menu) {
+                        self.menu_info = None;
                     }
                 }
             }
             Mode::Classic => {
                 if let Some(app) = &mut self.classic_state {
+                    if let Some(summary) = app.take_summary() {
+                        self.profiles.record(&summary);
+                    }
+                    self.toasts
+                        .extend(self.achievements.record(app.take_achievements()));
                     if let Some(signal) = app.update(ctx, frame) {
                         match signal.as_str() {
                             "menu" => {
+                                self.suspend_round();
                                 self.mode = Mode::Menu;
                                 self.classic_state = None;
                             }
                             "restart" => {
-                                *app = classic::ClassicApp::new();
+                                *app = classic::ClassicApp::new(self.settings);
+                            }
+                            "heatmap" => {
+                                self.mode = Mode::Heatmap;
+                                self.classic_state = None;
+                                self.heatmap_state = Some(heatmap::HeatmapScreen::new());
                             }
                             _ => {}
                         }
@@ -68,26 +251,156 @@ impl App for PoligonApp {
             }
             Mode::Advanced => {
                 if let Some(app) = &mut self.advanced_state {
+                    if let Some(index) = self.campaign_level
+                        && let Some(stars) = app.take_result()
+                    {
+                        self.progress.record(&self.campaign, index, stars);
+                    }
+                    if let Some(summary) = app.take_summary() {
+                        self.profiles.record(&summary);
+                    }
+                    self.toasts
+                        .extend(self.achievements.record(app.take_achievements()));
                     if let Some(signal) = app.update(ctx, frame) {
                         match signal.as_str() {
                             "menu" => {
-                                self.mode = Mode::Menu;
+                                self.suspend_round();
+                                self.mode = if self.campaign_level.take().is_some() {
+                                    Mode::Campaign
+                                } else {
+                                    Mode::Menu
+                                };
                                 self.advanced_state = None;
                             }
                             "restart" => {
-                                *app = advanced::AdvancedApp::new();
+                                *app = app.restart();
+                            }
+                            "heatmap" => {
+                                self.mode = Mode::Heatmap;
+                                self.campaign_level = None;
+                                self.advanced_state = None;
+                                self.heatmap_state = Some(heatmap::HeatmapScreen::new());
                             }
                             _ => {}
                         }
                     }
                 }
             }
+            Mode::TimeAttack => {
+                if let Some(app) = &mut self.time_attack_state {
+                    if let Some(summary) = app.take_summary() {
+                        self.profiles.record(&summary);
+                    }
+                    if let Some(signal) = app.update(ctx, frame) {
+                        match signal.as_str() {
+                            "menu" => {
+                                self.mode = Mode::Menu;
+                                self.time_attack_state = None;
+                            }
+                            "restart" => {
+                                *app = time_attack::TimeAttackApp::new();
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            Mode::Trainer => {
+                if let Some(app) = &mut self.trainer_state {
+                    if let Some(summary) = app.take_summary() {
+                        self.profiles.record(&summary);
+                    }
+                    if app.update(ctx, frame).as_deref() == Some("menu") {
+                        self.mode = Mode::Menu;
+                        self.trainer_state = None;
+                    }
+                }
+            }
+            Mode::Rhythm => {
+                if let Some(app) = &mut self.rhythm_state {
+                    if let Some(summary) = app.take_summary() {
+                        self.profiles.record(&summary);
+                    }
+                    if app.update(ctx, frame).as_deref() == Some("menu") {
+                        self.mode = Mode::Menu;
+                        self.rhythm_state = None;
+                    }
+                }
+            }
+            Mode::Replay => {
+                if let Some(app) = &mut self.replay_state
+                    && app.update(ctx, frame).as_deref() == Some("menu")
+                {
+                    self.mode = Mode::Menu;
+                    self.replay_state = None;
+                }
+            }
+            Mode::Profiles => {
+                if let Some(screen) = &mut self.profile_screen {
+                    match screen.update(ctx, &mut self.profiles).as_deref() {
+                        Some("switch") => {
+                            self.settings = difficulty::Settings::load();
+                            self.progress = campaign::CampaignProgress::load();
+                            self.achievements = achievement::AchievementBook::load();
+                            self.menu_info = None;
+                        }
+                        Some("menu") => {
+                            self.mode = Mode::Menu;
+                            self.profile_screen = None;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Mode::Stats => {
+                if let Some(screen) = &mut self.stats_state
+                    && screen.update(ctx).as_deref() == Some("menu")
+                {
+                    self.mode = Mode::Menu;
+                    self.stats_state = None;
+                }
+            }
+            Mode::Heatmap => {
+                if let Some(screen) = &mut self.heatmap_state
+                    && screen.update(ctx).as_deref() == Some("menu")
+                {
+                    self.mode = Mode::Menu;
+                    self.heatmap_state = None;
+                }
+            }
+            Mode::Achievements => {
+                if achievement::draw_achievements(ctx, &self.achievements) {
+                    self.mode = Mode::Menu;
+                }
+            }
+            Mode::Campaign => {
+                if let Some(result) = menu::draw_level_select(ctx, &self.campaign, &self.progress) {
+                    if result == "menu" {
+                        self.mode = Mode::Menu;
+                    } else if let Some(index) = result
+                        .strip_prefix("campaign:")
+                        .and_then(|index| index.parse::<usize>().ok())
+                    {
+                        match self.campaign.load_level(index) {
+                            Ok(level) => {
+                                self.mode = Mode::Advanced;
+                                self.campaign_level = Some(index);
+                                self.advanced_state =
+                                    Some(advanced::AdvancedApp::with_level(level, self.settings));
+                            }
+                            Err(err) => self.mode = Mode::Wip(err),
+                        }
+                    }
+                }
+            }
             Mode::Wip(msg) => {
                 if menu::draw_wip(ctx, msg) {
                     self.mode = Mode::Menu;
                 }
             }
         }
+
+        self.toasts.draw(ctx);
     }
 }
 
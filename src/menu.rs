@@ -1,10 +1,38 @@
+use crate::campaign::{Campaign, CampaignProgress};
+use crate::daily::DailyChallenge;
+use crate::difficulty::{ALL_DIFFICULTIES, Settings};
+use crate::level::Level;
+use crate::mutator::ALL_MUTATORS;
+use crate::resume::SavedRound;
 use egui::{Color32, Context, RichText};
 use once_cell::sync::OnceCell;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 static INTRO_START: OnceCell<Instant> = OnceCell::new();
 
-pub fn draw_menu(ctx: &Context) -> Option<String> {
+pub struct MenuInfo {
+    levels: Vec<PathBuf>,
+    saved: bool,
+    daily: DailyChallenge,
+}
+
+impl MenuInfo {
+    pub fn load() -> MenuInfo {
+        MenuInfo {
+            levels: Level::list(),
+            saved: SavedRound::exists(),
+            daily: DailyChallenge::today(),
+        }
+    }
+}
+
+pub fn draw_menu(
+    ctx: &Context,
+    settings: &mut Settings,
+    profile: &str,
+    info: &MenuInfo,
+) -> Option<String> {
     let now = Instant::now();
     let mut result = None;
 
@@ -41,10 +69,71 @@ pub fn draw_menu(ctx: &Context) -> Option<String> {
 
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.vertical_centered(|ui| {
-            ui.add_space(100.0);
+            ui.add_space(60.0);
             ui.heading(RichText::new("Poligon").size(36.0));
 
-            ui.add_space(40.0);
+            ui.add_space(20.0);
+
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 280.0);
+                for difficulty in ALL_DIFFICULTIES {
+                    if ui
+                        .selectable_label(
+                            settings.difficulty == difficulty,
+                            RichText::new(difficulty.name()).size(16.0),
+                        )
+                        .clicked()
+                    {
+                        settings.difficulty = difficulty;
+                        settings.save();
+                    }
+                }
+                if ui
+                    .checkbox(&mut settings.director, "Uyarlanabilir zorluk")
+                    .changed()
+                {
+                    settings.save();
+                }
+                if ui
+                    .checkbox(&mut settings.ghost, "Hayalet")
+                    .on_hover_text("Aynı kurulumdaki en iyi turunun nişangahıyla yarış")
+                    .changed()
+                {
+                    settings.save();
+                }
+                if ui
+                    .checkbox(&mut settings.reduced_motion, "Az hareket")
+                    .on_hover_text("Ekran sarsıntısını ve parçacık efektlerini kapat")
+                    .changed()
+                {
+                    settings.save();
+                }
+            });
+
+            egui::CollapsingHeader::new(
+                RichText::new(format!(
+                    "Mutatörler (puan x{:.2})",
+                    settings.mutators.score_factor()
+                ))
+                .size(16.0),
+            )
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for mutator in ALL_MUTATORS {
+                        let mut enabled = settings.mutators.contains(mutator);
+                        if ui
+                            .checkbox(&mut enabled, mutator.name())
+                            .on_hover_text(mutator.description())
+                            .changed()
+                        {
+                            settings.mutators.toggle(mutator);
+                            settings.save();
+                        }
+                    }
+                });
+            });
+
+            ui.add_space(10.0);
 
             ui.label(RichText::new("Geleneksel Poligon Deneyimi").size(20.0));
             ui.label(
@@ -77,6 +166,182 @@ pub fn draw_menu(ctx: &Context) -> Option<String> {
             if advanced_btn.clicked() {
                 result = Some("advanced".to_string());
             }
+
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.add_space(ui.available_width() / 2.0 - 380.0);
+                let survival_btn = ui.add_sized(
+                    [120.0, 32.0],
+                    egui::Button::new(RichText::new("Hayatta Kal").size(18.0)),
+                );
+                if survival_btn.clicked() {
+                    result = Some("survival".to_string());
+                }
+                let daily = &info.daily;
+                let daily_btn = ui
+                    .add_sized(
+                        [120.0, 32.0],
+                        egui::Button::new(RichText::new("Günlük Görev").size(18.0)),
+                    )
+                    .on_hover_text(format!("{}: {}", daily.date, daily.mutators.names()));
+                if daily_btn.clicked() {
+                    result = Some("daily".to_string());
+                }
+                let time_attack_btn = ui.add_sized(
+                    [120.0, 32.0],
+                    egui::Button::new(RichText::new("Zamana Karşı").size(18.0)),
+                );
+                if time_attack_btn.clicked() {
+                    result = Some("time_attack".to_string());
+                }
+                let campaign_btn = ui.add_sized(
+                    [120.0, 32.0],
+                    egui::Button::new(RichText::new("Kampanya").size(18.0)),
+                );
+                if campaign_btn.clicked() {
+                    result = Some("campaign".to_string());
+                }
+                let trainer_btn = ui.add_sized(
+                    [120.0, 32.0],
+                    egui::Button::new(RichText::new("Antrenman").size(18.0)),
+                );
+                if trainer_btn.clicked() {
+                    result = Some("trainer".to_string());
+                }
+                let rhythm_btn = ui.add_sized(
+                    [120.0, 32.0],
+                    egui::Button::new(RichText::new("Ritim").size(18.0)),
+                );
+                if rhythm_btn.clicked() {
+                    result = Some("rhythm".to_string());
+                }
+            });
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                let saved = info.saved;
+                let width = if saved { 632.0 } else { 504.0 };
+                ui.add_space(ui.available_width() / 2.0 - width / 2.0);
+                if saved {
+                    let resume_btn = ui
+                        .add_sized(
+                            [120.0, 28.0],
+                            egui::Button::new(RichText::new("Devam Et").size(16.0)),
+                        )
+                        .on_hover_text("Yarıda bırakılan turu sürdür");
+                    if resume_btn.clicked() {
+                        result = Some("resume".to_string());
+                    }
+                }
+                let replays_btn = ui.add_sized(
+                    [120.0, 28.0],
+                    egui::Button::new(RichText::new("Tekrarlar").size(16.0)),
+                );
+                if replays_btn.clicked() {
+                    result = Some("replays".to_string());
+                }
+                let stats_btn = ui.add_sized(
+                    [120.0, 28.0],
+                    egui::Button::new(RichText::new("İstatistikler").size(16.0)),
+                );
+                if stats_btn.clicked() {
+                    result = Some("stats".to_string());
+                }
+                let heatmap_btn = ui.add_sized(
+                    [120.0, 28.0],
+                    egui::Button::new(RichText::new("Isı Haritası").size(16.0)),
+                );
+                if heatmap_btn.clicked() {
+                    result = Some("heatmap".to_string());
+                }
+                let achievements_btn = ui.add_sized(
+                    [120.0, 28.0],
+                    egui::Button::new(RichText::new("Başarımlar").size(16.0)),
+                );
+                if achievements_btn.clicked() {
+                    result = Some("achievements".to_string());
+                }
+            });
+
+            if !info.levels.is_empty() {
+                ui.add_space(10.0);
+                ui.label(RichText::new("Senaryolar").size(16.0).color(Color32::GRAY));
+                ui.horizontal_wrapped(|ui| {
+                    for path in &info.levels {
+                        let label = path
+                            .file_stem()
+                            .map(|stem| stem.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        if ui.button(label).clicked() {
+                            result = Some(format!("level:{}", path.display()));
+                        }
+                    }
+                });
+            }
+        });
+    });
+
+    egui::Area::new(egui::Id::new("profile_button"))
+        .fixed_pos(egui::pos2(10.0, 10.0))
+        .show(ctx, |ui| {
+            if ui
+                .button(RichText::new(format!("Profil: {}", profile)).size(16.0))
+                .on_hover_text("Profilleri ve istatistikleri göster")
+                .clicked()
+            {
+                result = Some("profiles".to_string());
+            }
+        });
+
+    result
+}
+
+pub fn draw_level_select(
+    ctx: &Context,
+    campaign: &Campaign,
+    progress: &CampaignProgress,
+) -> Option<String> {
+    let mut result = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(60.0);
+            ui.heading(RichText::new("Kampanya").size(36.0));
+            ui.add_space(30.0);
+
+            if campaign.levels.is_empty() {
+                ui.label(RichText::new("Kampanya bölümü bulunamadı.").size(18.0));
+            }
+
+            for (index, level) in campaign.levels.iter().enumerate() {
+                let unlocked = progress.is_unlocked(campaign, index);
+                let stars = progress.stars(campaign, index).min(3) as usize;
+                let text = if unlocked {
+                    format!(
+                        "{}. {}   {}{}",
+                        index + 1,
+                        level.title,
+                        "★".repeat(stars),
+                        "☆".repeat(3 - stars)
+                    )
+                } else {
+                    format!("{}. {}   (Kilitli)", index + 1, level.title)
+                };
+                let button = ui.add_enabled(
+                    unlocked,
+                    egui::Button::new(RichText::new(text).size(20.0))
+                        .min_size(egui::vec2(320.0, 40.0)),
+                );
+                if button.clicked() {
+                    result = Some(format!("campaign:{}", index));
+                }
+                ui.add_space(8.0);
+            }
+
+            ui.add_space(30.0);
+            if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                result = Some("menu".to_string());
+            }
         });
     });
 
@@ -0,0 +1,125 @@
+use rodio::{Decoder, OutputStreamHandle, Sink, Source};
+use std::io::BufReader;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+const WINDOW: usize = 1024;
+const HOP: usize = 512;
+const MIN_ONSET_GAP: f32 = 0.3;
+
+struct Tracked<S> {
+    inner: S,
+    played: Arc<AtomicU64>,
+}
+
+pub struct MusicPlayer {
+    sink: Sink,
+    played: Arc<AtomicU64>,
+    samples_per_sec: u64,
+}
+
+impl<S: Source> Iterator for Tracked<S>
+where
+    S::Item: rodio::Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        let sample = self.inner.next()?;
+        self.played.fetch_add(1, Ordering::Relaxed);
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for Tracked<S>
+where
+    S::Item: rodio::Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
+
+impl MusicPlayer {
+    pub fn play(handle: &OutputStreamHandle, path: &str) -> Result<MusicPlayer, String> {
+        let file = std::fs::File::open(path).map_err(|e| format!("{} açılamadı: {}", path, e))?;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| format!("{} çözümlenemedi: {}", path, e))?;
+        let samples_per_sec = source.sample_rate() as u64 * source.channels() as u64;
+        let played = Arc::new(AtomicU64::new(0));
+        let sink = Sink::try_new(handle).map_err(|e| e.to_string())?;
+        sink.append(Tracked {
+            inner: source,
+            played: played.clone(),
+        });
+        Ok(MusicPlayer {
+            sink,
+            played,
+            samples_per_sec,
+        })
+    }
+
+    pub fn position(&self) -> Duration {
+        let played = self.played.load(Ordering::Relaxed);
+        Duration::from_secs_f64(played as f64 / self.samples_per_sec.max(1) as f64)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.sink.empty()
+    }
+}
+
+pub fn detect_onsets(path: &str) -> Result<Vec<f32>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("{} açılamadı: {}", path, e))?;
+    let source =
+        Decoder::new(BufReader::new(file)).map_err(|e| format!("{} çözümlenemedi: {}", path, e))?;
+    let channels = source.channels().max(1) as usize;
+    let sample_rate = source.sample_rate() as f32;
+
+    let samples: Vec<f32> = source
+        .map(|sample| sample as f32 / i16::MAX as f32)
+        .collect();
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let energy: Vec<f32> = (0..mono.len().saturating_sub(WINDOW) / HOP)
+        .map(|i| {
+            let window = &mono[i * HOP..i * HOP + WINDOW];
+            (window.iter().map(|s| s * s).sum::<f32>() / WINDOW as f32).sqrt()
+        })
+        .collect();
+    let flux: Vec<f32> = std::iter::once(0.0)
+        .chain(energy.windows(2).map(|pair| (pair[1] - pair[0]).max(0.0)))
+        .collect();
+
+    let mut onsets: Vec<f32> = Vec::new();
+    for i in 1..flux.len().saturating_sub(1) {
+        let local = &flux[i.saturating_sub(8)..(i + 8).min(flux.len())];
+        let threshold = local.iter().sum::<f32>() / local.len() as f32 * 1.5 + 0.01;
+        let is_peak = flux[i] > threshold && flux[i] >= flux[i - 1] && flux[i] >= flux[i + 1];
+        let time = (i * HOP + WINDOW / 2) as f32 / sample_rate;
+        if is_peak
+            && onsets
+                .last()
+                .is_none_or(|last| time - last >= MIN_ONSET_GAP)
+        {
+            onsets.push(time);
+        }
+    }
+    Ok(onsets)
+}
@@ -0,0 +1,166 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Mutator {
+    LowGravity,
+    TinyTargets,
+    OneShot,
+    Invisible,
+    Mirrored,
+    ElitesOnly,
+    NoHealth,
+    DoubleSpeed,
+}
+
+pub const ALL_MUTATORS: [Mutator; 8] = [
+    Mutator::LowGravity,
+    Mutator::TinyTargets,
+    Mutator::OneShot,
+    Mutator::Invisible,
+    Mutator::Mirrored,
+    Mutator::ElitesOnly,
+    Mutator::NoHealth,
+    Mutator::DoubleSpeed,
+];
+
+pub const DOUBLE_SPEED: f32 = 2.0;
+pub const TINY_SCALE: f32 = 0.5;
+pub const FLASH_TIME: f32 = 0.25;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(from = "Vec<Mutator>", into = "Vec<Mutator>")]
+pub struct MutatorSet {
+    bits: u8,
+}
+
+impl Mutator {
+    pub fn name(self) -> &'static str {
+        match self {
+            Mutator::LowGravity => "Düşük Yerçekimi",
+            Mutator::TinyTargets => "Minik Hedefler",
+            Mutator::OneShot => "Tek Atış",
+            Mutator::Invisible => "Görünmez Düşmanlar",
+            Mutator::Mirrored => "Ayna Kontroller",
+            Mutator::ElitesOnly => "Sadece Elitler",
+            Mutator::NoHealth => "Sağlık Kutusu Yok",
+            Mutator::DoubleSpeed => "Çift Hız",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Mutator::LowGravity => "Hedefler havada süzülür",
+            Mutator::TinyTargets => "Hedefler yarı boyutta",
+            Mutator::OneShot => "Iskalayan atış turu bitirir",
+            Mutator::Invisible => "Düşmanlar yalnızca ateş ederken görünür",
+            Mutator::Mirrored => "Nişangah yatayda ters döner",
+            Mutator::ElitesOnly => "Yalnızca elit düşmanlar, sivil yok",
+            Mutator::NoHealth => "Sağlık kutusu çıkmaz",
+            Mutator::DoubleSpeed => "Oyun iki kat hızlı akar",
+        }
+    }
+
+    pub fn score_factor(self) -> f32 {
+        match self {
+            Mutator::LowGravity => 1.1,
+            Mutator::TinyTargets => 1.5,
+            Mutator::OneShot => 1.25,
+            Mutator::Invisible => 1.5,
+            Mutator::Mirrored => 1.25,
+            Mutator::ElitesOnly => 1.0,
+            Mutator::NoHealth => 1.25,
+            Mutator::DoubleSpeed => 1.5,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl MutatorSet {
+    pub fn contains(self, mutator: Mutator) -> bool {
+        self.bits & mutator.bit() != 0
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        self.bits ^= mutator.bit();
+    }
+
+    pub fn bits(self) -> u8 {
+        self.bits
+    }
+
+    pub fn from_bits(bits: u8) -> MutatorSet {
+        MutatorSet { bits }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = Mutator> {
+        ALL_MUTATORS
+            .into_iter()
+            .filter(move |mutator| self.contains(*mutator))
+    }
+
+    pub fn names(self) -> String {
+        self.iter()
+            .map(|mutator| mutator.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn score_factor(self) -> f32 {
+        self.iter().map(|mutator| mutator.score_factor()).product()
+    }
+
+    pub fn summary(self) -> String {
+        format!("{} (puan x{:.2})", self.names(), self.score_factor())
+    }
+
+    pub fn target_rect(self, rect: egui::Rect) -> egui::Rect {
+        if self.contains(Mutator::TinyTargets) {
+            egui::Rect::from_center_size(rect.center(), rect.size() * TINY_SCALE)
+        } else {
+            rect
+        }
+    }
+
+    pub fn float_offset(self, t: f32, phase: f32) -> egui::Vec2 {
+        if !self.contains(Mutator::LowGravity) {
+            return egui::Vec2::ZERO;
+        }
+        let phase = phase * 0.05;
+        egui::vec2(
+            (t * 0.9 + phase).sin() * 25.0,
+            (t * 1.3 + phase).cos() * 35.0 - t.min(3.0) * 15.0,
+        )
+    }
+
+    pub fn aim(self, pos: egui::Pos2, field: egui::Rect) -> egui::Pos2 {
+        if self.contains(Mutator::Mirrored) {
+            egui::pos2(field.left() + field.right() - pos.x, pos.y)
+        } else {
+            pos
+        }
+    }
+}
+
+impl From<Vec<Mutator>> for MutatorSet {
+    fn from(mutators: Vec<Mutator>) -> Self {
+        let mut set = MutatorSet::default();
+        for mutator in mutators {
+            set.bits |= mutator.bit();
+        }
+        set
+    }
+}
+
+impl From<MutatorSet> for Vec<Mutator> {
+    fn from(set: MutatorSet) -> Self {
+        set.iter().collect()
+    }
+}
@@ -0,0 +1,287 @@
+use crate::advanced::EnemyType;
+use crate::event::{GameEvent, KillCause, Subscriber};
+use eframe::egui;
+use egui::{Color32, Pos2, Vec2};
+use rand::Rng;
+use std::time::Instant;
+
+const GRAVITY: f32 = 900.0;
+const PARTICLE_LIFE: f32 = 0.5;
+const FLOATER_LIFE: f32 = 0.9;
+const FLOATER_RISE: f32 = 40.0;
+const MARKER_LIFE: f32 = 0.15;
+const SHAKE_LIFE: f32 = 0.3;
+const VIGNETTE_LIFE: f32 = 0.6;
+const VIGNETTE_WIDTH: f32 = 120.0;
+
+struct Particle {
+    origin: Pos2,
+    velocity: Vec2,
+    color: Color32,
+    born: Instant,
+}
+
+struct Floater {
+    pos: Pos2,
+    text: String,
+    color: Color32,
+    born: Instant,
+}
+
+struct HitMarker {
+    pos: Pos2,
+    killed: bool,
+    born: Instant,
+}
+
+pub struct Juice {
+    reduced_motion: bool,
+    clock: Instant,
+    particles: Vec<Particle>,
+    floaters: Vec<Floater>,
+    markers: Vec<HitMarker>,
+    shot_killed: bool,
+    shake: Option<(Instant, f32)>,
+    vignette: Option<(Instant, f32)>,
+}
+
+fn age(born: Instant, now: Instant) -> f32 {
+    now.saturating_duration_since(born).as_secs_f32()
+}
+
+fn fade(color: Color32, t: f32) -> Color32 {
+    color.gamma_multiply((1.0 - t).clamp(0.0, 1.0))
+}
+
+impl Juice {
+    pub fn new(reduced_motion: bool, now: Instant) -> Self {
+        Juice {
+            reduced_motion,
+            clock: now,
+            particles: Vec::new(),
+            floaters: Vec::new(),
+            markers: Vec::new(),
+            shot_killed: false,
+            shake: None,
+            vignette: None,
+        }
+    }
+
+    pub fn advance(&mut self, now: Instant) {
+        self.clock = now;
+        self.particles
+            .retain(|particle| age(particle.born, now) < PARTICLE_LIFE);
+        self.floaters
+            .retain(|floater| age(floater.born, now) < FLOATER_LIFE);
+        self.markers
+            .retain(|marker| age(marker.born, now) < MARKER_LIFE);
+        self.shake = self.shake.filter(|(t0, _)| age(*t0, now) < SHAKE_LIFE);
+        self.vignette = self
+            .vignette
+            .filter(|(t0, _)| age(*t0, now) < VIGNETTE_LIFE);
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.particles.is_empty()
+            || !self.floaters.is_empty()
+            || !self.markers.is_empty()
+            || self.shake.is_some()
+            || self.vignette.is_some()
+    }
+
+    fn burst(&mut self, pos: Pos2, count: usize, speed: f32, colors: &[Color32]) {
+        if self.reduced_motion {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let velocity = Vec2::angled(angle) * rng.gen_range(speed * 0.4..=speed);
+            self.particles.push(Particle {
+                origin: pos,
+                velocity: velocity - Vec2::new(0.0, speed * 0.3),
+                color: colors[rng.gen_range(0..colors.len())],
+                born: self.clock,
+            });
+        }
+    }
+
+    fn shake_screen(&mut self, strength: f32) {
+        let current = self.shake.map_or(0.0, |(t0, strength)| {
+            strength * (1.0 - age(t0, self.clock) / SHAKE_LIFE)
+        });
+        if strength >= current {
+            self.shake = Some((self.clock, strength));
+        }
+    }
+
+    pub fn shake_offset(&self, now: Instant) -> Vec2 {
+        let Some((t0, strength)) = self.shake else {
+            return Vec2::ZERO;
+        };
+        if self.reduced_motion {
+            return Vec2::ZERO;
+        }
+        let t = age(t0, now);
+        let decay = (1.0 - t / SHAKE_LIFE).max(0.0);
+        Vec2::new((t * 97.0).sin(), (t * 71.0).cos()) * strength * decay
+    }
+
+    pub fn draw_world(&self, painter: &egui::Painter, now: Instant) {
+        for particle in &self.particles {
+            let t = age(particle.born, now);
+            let pos =
+                particle.origin + particle.velocity * t + Vec2::new(0.0, 0.5 * GRAVITY * t * t);
+            painter.circle_filled(
+                pos,
+                3.0 * (1.0 - t / PARTICLE_LIFE) + 1.0,
+                fade(particle.color, t / PARTICLE_LIFE),
+            );
+        }
+
+        for floater in &self.floaters {
+            let t = age(floater.born, now) / FLOATER_LIFE;
+            let rise = if self.reduced_motion {
+                0.0
+            } else {
+                FLOATER_RISE * t
+            };
+            painter.text(
+                floater.pos - Vec2::new(0.0, rise),
+                egui::Align2::CENTER_BOTTOM,
+                &floater.text,
+                egui::FontId::proportional(22.0),
+                fade(floater.color, t * t),
+            );
+        }
+    }
+
+    pub fn draw_overlay(&self, painter: &egui::Painter, field: egui::Rect, now: Instant) {
+        for marker in &self.markers {
+            let t = age(marker.born, now) / MARKER_LIFE;
+            let color = if marker.killed {
+                Color32::RED
+            } else {
+                Color32::WHITE
+            };
+            let spread = if self.reduced_motion { 0.0 } else { 4.0 * t };
+            for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+                let dir = Vec2::new(dx, dy);
+                painter.line_segment(
+                    [
+                        marker.pos + dir * (6.0 + spread),
+                        marker.pos + dir * (12.0 + spread),
+                    ],
+                    (2.0, fade(color, t)),
+                );
+            }
+        }
+
+        if let Some((t0, strength)) = self.vignette {
+            let t = age(t0, now) / VIGNETTE_LIFE;
+            let pulse = if self.reduced_motion {
+                1.0
+            } else {
+                0.75 + 0.25 * (t * std::f32::consts::TAU * 2.0).cos()
+            };
+            let alpha = (strength * (1.0 - t) * pulse * 255.0).clamp(0.0, 255.0) as u8;
+            draw_vignette(
+                painter,
+                field,
+                Color32::from_rgba_unmultiplied(200, 0, 0, alpha),
+            );
+        }
+    }
+}
+
+fn draw_vignette(painter: &egui::Painter, field: egui::Rect, color: Color32) {
+    let inner = field.shrink(VIGNETTE_WIDTH);
+    let mut mesh = egui::Mesh::default();
+    for (outer, inner) in [
+        (field.left_top(), inner.left_top()),
+        (field.right_top(), inner.right_top()),
+        (field.right_bottom(), inner.right_bottom()),
+        (field.left_bottom(), inner.left_bottom()),
+    ] {
+        mesh.colored_vertex(outer, color);
+        mesh.colored_vertex(inner, Color32::TRANSPARENT);
+    }
+    for side in 0..4u32 {
+        let (outer, inner) = (side * 2, side * 2 + 1);
+        let (next_outer, next_inner) = ((side * 2 + 2) % 8, (side * 2 + 3) % 8);
+        mesh.add_triangle(outer, next_outer, inner);
+        mesh.add_triangle(inner, next_outer, next_inner);
+    }
+    painter.add(mesh);
+}
+
+impl Subscriber for Juice {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::ShotFired { pos, hit: true } => {
+                self.markers.push(HitMarker {
+                    pos,
+                    killed: self.shot_killed,
+                    born: self.clock,
+                });
+                self.shot_killed = false;
+            }
+            GameEvent::EnemyHit {
+                enemy_type: EnemyType::Elite,
+                pos,
+                killed: false,
+            } => self.burst(
+                pos,
+                12,
+                260.0,
+                &[
+                    Color32::YELLOW,
+                    Color32::from_rgb(255, 170, 40),
+                    Color32::WHITE,
+                ],
+            ),
+            GameEvent::EnemyKilled { pos, cause, .. } => {
+                if cause == KillCause::Shot {
+                    self.shot_killed = true;
+                }
+                self.burst(
+                    pos,
+                    10,
+                    180.0,
+                    &[Color32::from_rgb(150, 0, 0), Color32::from_rgb(200, 20, 20)],
+                );
+            }
+            GameEvent::ScoreAwarded { points, pos } if points != 0 => {
+                self.floaters.push(Floater {
+                    pos,
+                    text: format!("{:+}", points),
+                    color: if points > 0 {
+                        Color32::GOLD
+                    } else {
+                        Color32::RED
+                    },
+                    born: self.clock,
+                });
+            }
+            GameEvent::EnemyFired { blocked: false, .. } => self.shake_screen(4.0),
+            GameEvent::TntExploded { center, .. } => {
+                self.shake_screen(10.0);
+                self.burst(
+                    center,
+                    20,
+                    360.0,
+                    &[
+                        Color32::from_rgb(255, 140, 0),
+                        Color32::YELLOW,
+                        Color32::DARK_GRAY,
+                    ],
+                );
+            }
+            GameEvent::TimeLost { seconds } => {
+                let strength = (0.35 + seconds as f32 * 0.05).min(0.8);
+                self.vignette = Some((self.clock, strength));
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use crate::advanced::EnemyType;
+use crate::difficulty::Difficulty;
+use crate::event::{GameEvent, Subscriber};
+use crate::heatmap::{HeatLayer, Heatmap};
+use crate::stats::RoundLog;
+use crate::storage;
+use crate::supply::SupplyBoxType;
+use crate::survival;
+use egui::{Color32, Context, RichText};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+const INDEX_FILE: &str = "profiles.toml";
+const STATS_FILE: &str = "stats.toml";
+const DEFAULT_NAME: &str = "Oyuncu";
+const MAX_NAME_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+struct ProfileIndex {
+    active: u32,
+    next_id: u32,
+    profiles: Vec<ProfileEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ProfileEntry {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ProfileStats {
+    #[serde(default)]
+    pub rounds: u32,
+    #[serde(default)]
+    pub play_secs: f32,
+    #[serde(default)]
+    pub shots: u32,
+    #[serde(default)]
+    pub hits: u32,
+    #[serde(default)]
+    pub kills: BTreeMap<EnemyType, u32>,
+    #[serde(default)]
+    pub boxes: BTreeMap<SupplyBoxType, u32>,
+    #[serde(default)]
+    pub best: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub best_times: BTreeMap<String, f32>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct RoundTally {
+    #[serde(default)]
+    pub shots: u32,
+    #[serde(default)]
+    pub hits: u32,
+    #[serde(default)]
+    pub kills: BTreeMap<EnemyType, u32>,
+    #[serde(default)]
+    pub boxes: BTreeMap<SupplyBoxType, u32>,
+    #[serde(default)]
+    pub reactions: Vec<u32>,
+    #[serde(default)]
+    pub heat: Heatmap,
+}
+
+pub struct RoundSummary {
+    pub mode: String,
+    pub difficulty: Difficulty,
+    pub score: u32,
+    pub secs: f32,
+    pub timed: bool,
+    pub tally: RoundTally,
+}
+
+pub struct Profiles {
+    index: ProfileIndex,
+    pub stats: ProfileStats,
+}
+
+pub struct ProfileScreen {
+    new_name: String,
+}
+
+impl Default for ProfileIndex {
+    fn default() -> Self {
+        ProfileIndex {
+            active: 0,
+            next_id: 1,
+            profiles: vec![ProfileEntry {
+                id: 0,
+                name: DEFAULT_NAME.to_string(),
+            }],
+        }
+    }
+}
+
+fn profile_dir(id: u32) -> String {
+    if id == 0 {
+        String::new()
+    } else {
+        format!("profile_{}", id)
+    }
+}
+
+impl RoundTally {
+    pub fn shot(&mut self, hit: bool) {
+        self.shots += 1;
+        if hit {
+            self.hits += 1;
+        }
+    }
+
+    pub fn kill(&mut self, enemy_type: EnemyType) {
+        *self.kills.entry(enemy_type).or_insert(0) += 1;
+    }
+
+    pub fn collect(&mut self, kind: SupplyBoxType) {
+        *self.boxes.entry(kind).or_insert(0) += 1;
+    }
+
+    pub fn react(&mut self, elapsed: Duration) {
+        self.reactions
+            .push(elapsed.as_millis().min(u32::MAX as u128) as u32);
+    }
+}
+
+impl Subscriber for RoundTally {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::ShotFired { pos, hit } => {
+                self.shot(hit);
+                self.heat.shot(pos, hit);
+            }
+            GameEvent::EnemySpawned { pos, .. } => self.heat.add(HeatLayer::Spawns, pos),
+            GameEvent::EnemyKilled {
+                enemy_type,
+                reaction,
+                ..
+            } => {
+                self.kill(enemy_type);
+                if let Some(elapsed) = reaction {
+                    self.react(elapsed);
+                }
+            }
+            GameEvent::SupplyCollected { kind } => self.collect(kind),
+            _ => {}
+        }
+    }
+}
+
+impl ProfileStats {
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots as f32
+        }
+    }
+
+    fn record(&mut self, summary: &RoundSummary) {
+        self.rounds += 1;
+        self.play_secs += summary.secs;
+        self.shots += summary.tally.shots;
+        self.hits += summary.tally.hits;
+        for (enemy_type, count) in &summary.tally.kills {
+            *self.kills.entry(*enemy_type).or_insert(0) += count;
+        }
+        for (kind, count) in &summary.tally.boxes {
+            *self.boxes.entry(*kind).or_insert(0) += count;
+        }
+        if summary.timed {
+            self.best.remove(&summary.mode);
+            let best = self
+                .best_times
+                .entry(summary.mode.clone())
+                .or_insert(summary.secs);
+            *best = best.min(summary.secs);
+        } else {
+            let best = self.best.entry(summary.mode.clone()).or_insert(0);
+            *best = (*best).max(summary.score);
+        }
+    }
+}
+
+impl Profiles {
+    pub fn load() -> Profiles {
+        let index: ProfileIndex = storage::load_shared_toml(INDEX_FILE);
+        storage::set_profile_dir(&profile_dir(index.active));
+        Profiles {
+            index,
+            stats: storage::load_toml(STATS_FILE),
+        }
+    }
+
+    pub fn active_name(&self) -> &str {
+        self.index
+            .profiles
+            .iter()
+            .find(|profile| profile.id == self.index.active)
+            .map_or(DEFAULT_NAME, |profile| profile.name.as_str())
+    }
+
+    pub fn select(&mut self, id: u32) {
+        self.index.active = id;
+        storage::save_shared_toml(INDEX_FILE, &self.index);
+        storage::set_profile_dir(&profile_dir(id));
+        self.stats = storage::load_toml(STATS_FILE);
+    }
+
+    pub fn create(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty()
+            || self
+                .index
+                .profiles
+                .iter()
+                .any(|profile| profile.name == name)
+        {
+            return false;
+        }
+        let id = self.index.next_id;
+        self.index.next_id += 1;
+        self.index.profiles.push(ProfileEntry {
+            id,
+            name: name.to_string(),
+        });
+        self.select(id);
+        true
+    }
+
+    pub fn record(&mut self, summary: &RoundSummary) {
+        self.stats.record(summary);
+        storage::save_toml(STATS_FILE, &self.stats);
+        RoundLog::load().record(summary);
+        let mut heatmap = Heatmap::load();
+        heatmap.merge(&summary.tally.heat);
+        heatmap.save();
+    }
+}
+
+impl ProfileScreen {
+    pub fn new() -> Self {
+        ProfileScreen {
+            new_name: String::new(),
+        }
+    }
+
+    pub fn update(&mut self, ctx: &Context, profiles: &mut Profiles) -> Option<String> {
+        let mut signal = None;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(30.0);
+                ui.heading(RichText::new("Profiller").size(36.0));
+            });
+            ui.add_space(20.0);
+
+            ui.columns(2, |columns| {
+                let ui = &mut columns[0];
+                ui.label(RichText::new("Oyuncular").size(20.0));
+                ui.add_space(10.0);
+                for profile in profiles.index.profiles.clone() {
+                    let active = profile.id == profiles.index.active;
+                    let text = if active {
+                        RichText::new(format!("▶ {}", profile.name)).color(Color32::GOLD)
+                    } else {
+                        RichText::new(&profile.name)
+                    };
+                    if ui.button(text.size(18.0)).clicked() && !active {
+                        profiles.select(profile.id);
+                        signal = Some("switch".to_string());
+                    }
+                }
+                ui.add_space(20.0);
+                ui.label(RichText::new("Yeni profil").size(16.0));
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_name)
+                            .char_limit(MAX_NAME_LEN)
+                            .desired_width(150.0),
+                    );
+                    if ui.button("Oluştur").clicked() && profiles.create(&self.new_name) {
+                        self.new_name.clear();
+                        signal = Some("switch".to_string());
+                    }
+                });
+
+                let ui = &mut columns[1];
+                ui.label(RichText::new(profiles.active_name()).size(20.0));
+                ui.add_space(10.0);
+                draw_stats(ui, &profiles.stats);
+            });
+
+            ui.vertical_centered(|ui| {
+                ui.add_space(20.0);
+                if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                    signal = Some("menu".to_string());
+                }
+            });
+        });
+
+        signal
+    }
+}
+
+fn draw_stats(ui: &mut egui::Ui, stats: &ProfileStats) {
+    egui::ScrollArea::vertical()
+        .max_height(380.0)
+        .show(ui, |ui| {
+            egui::Grid::new("profile_stats")
+                .spacing([20.0, 4.0])
+                .show(ui, |ui| {
+                    ui.label("Oynanan tur");
+                    ui.label(stats.rounds.to_string());
+                    ui.end_row();
+                    ui.label("Oyun süresi");
+                    ui.label(survival::format_time(stats.play_secs));
+                    ui.end_row();
+                    ui.label("İsabet");
+                    ui.label(format!(
+                        "%{:.0} ({}/{})",
+                        stats.accuracy() * 100.0,
+                        stats.hits,
+                        stats.shots
+                    ));
+                    ui.end_row();
+
+                    for (enemy_type, count) in &stats.kills {
+                        ui.label(format!("Vurulan {}", enemy_type.name()));
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                    for (kind, count) in &stats.boxes {
+                        ui.label(kind.spec().name);
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+
+            if !stats.best.is_empty() {
+                ui.add_space(10.0);
+                ui.label(RichText::new("En İyi Skorlar").size(18.0));
+                egui::Grid::new("profile_best")
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        for (mode, score) in &stats.best {
+                            ui.label(mode);
+                            ui.label(format!("{} puan", score));
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            if !stats.best_times.is_empty() {
+                ui.add_space(10.0);
+                ui.label(RichText::new("En İyi Süreler").size(18.0));
+                egui::Grid::new("profile_best_times")
+                    .spacing([20.0, 4.0])
+                    .show(ui, |ui| {
+                        for (mode, secs) in &stats.best_times {
+                            ui.label(mode);
+                            ui.label(survival::format_time(*secs));
+                            ui.end_row();
+                        }
+                    });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(mode: &str, score: u32, secs: f32, timed: bool) -> RoundSummary {
+        RoundSummary {
+            mode: mode.to_string(),
+            difficulty: Difficulty::default(),
+            score,
+            secs,
+            timed,
+            tally: RoundTally::default(),
+        }
+    }
+
+    #[test]
+    fn timed_rounds_keep_the_fastest_time() {
+        let mut stats = ProfileStats::default();
+        stats.record(&summary("Zamana Karşı", 20, 31.5, true));
+        stats.record(&summary("Zamana Karşı", 20, 27.0, true));
+        stats.record(&summary("Zamana Karşı", 20, 40.0, true));
+        stats.record(&summary("Klasik", 12, 20.0, false));
+
+        assert_eq!(stats.best_times.get("Zamana Karşı"), Some(&27.0));
+        assert!(!stats.best.contains_key("Zamana Karşı"));
+        assert_eq!(stats.best.get("Klasik"), Some(&12));
+    }
+}
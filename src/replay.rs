@@ -0,0 +1,609 @@
+use crate::advanced::AdvancedApp;
+use crate::classic::ClassicApp;
+use crate::daily;
+use crate::difficulty::{ALL_DIFFICULTIES, Settings};
+use crate::level::Level;
+use crate::mutator::MutatorSet;
+use crate::storage;
+use crate::survival;
+use eframe::egui;
+use egui::RichText;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 4] = b"PRPL";
+const VERSION: u8 = 2;
+const REPLAY_PREFIX: &str = "replay_";
+const GHOST_PREFIX: &str = "ghost_";
+const MAX_REPLAYS: usize = 20;
+const SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+const FLAG_POINTER: u8 = 1;
+const FLAG_CLICK: u8 = 2;
+const FLAG_RELOAD: u8 = 4;
+const ACTION_NONE: u8 = 0;
+const ACTION_NEXT_WAVE: u8 = 255;
+
+#[derive(Clone, Copy, Default)]
+pub struct FrameInput {
+    pub dt: Duration,
+    pub pointer: Option<egui::Pos2>,
+    pub click: bool,
+    pub reload: bool,
+    pub action: Option<ShopAction>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ShopAction {
+    Buy(usize),
+    NextWave,
+}
+
+pub enum ReplayKind {
+    Advanced,
+    Survival,
+    Level(String),
+    Daily,
+    Classic,
+}
+
+pub struct Replay {
+    pub kind: ReplayKind,
+    pub seed: u64,
+    pub settings: Settings,
+    pub recorded_at: u64,
+    pub score: u32,
+    pub frames: Vec<FrameInput>,
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+pub struct ReplayViewer {
+    screen: Screen,
+    last_frame: Instant,
+}
+
+enum Screen {
+    Select(Vec<(String, Replay)>),
+    Playing(Box<Playback>),
+    Error(String),
+}
+
+struct Playback {
+    replay: Replay,
+    app: ReplayRound,
+    times: Vec<Duration>,
+    index: usize,
+    clock: Duration,
+    playing: bool,
+    speed: f32,
+}
+
+enum ReplayRound {
+    Classic(Box<ClassicApp>),
+    Advanced(Box<AdvancedApp>),
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+}
+
+impl ReplayKind {
+    fn name(&self) -> String {
+        match self {
+            ReplayKind::Advanced => "Gelişmiş".to_string(),
+            ReplayKind::Survival => "Hayatta Kalma".to_string(),
+            ReplayKind::Level(source) => match Level::parse(source) {
+                Ok(level) => format!("Senaryo: {}", level.name),
+                Err(_) => "Senaryo".to_string(),
+            },
+            ReplayKind::Daily => "Günlük Görev".to_string(),
+            ReplayKind::Classic => "Klasik".to_string(),
+        }
+    }
+
+    fn tag(&self) -> (u8, &str) {
+        match self {
+            ReplayKind::Advanced => (0, ""),
+            ReplayKind::Survival => (1, ""),
+            ReplayKind::Level(source) => (2, source.as_str()),
+            ReplayKind::Daily => (3, ""),
+            ReplayKind::Classic => (4, ""),
+        }
+    }
+}
+
+fn difficulty_index(settings: Settings) -> u8 {
+    ALL_DIFFICULTIES
+        .iter()
+        .position(|difficulty| *difficulty == settings.difficulty)
+        .unwrap_or(1) as u8
+}
+
+fn ghost_name(kind: &ReplayKind, settings: Settings, seed: u64) -> String {
+    let (tag, source) = kind.tag();
+    let seed = if matches!(kind, ReplayKind::Daily) {
+        seed
+    } else {
+        0
+    };
+    let header = [
+        tag,
+        difficulty_index(settings),
+        settings.director as u8,
+        settings.mutators.bits(),
+    ];
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in header
+        .into_iter()
+        .chain(source.bytes())
+        .chain(seed.to_le_bytes())
+    {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{}{:016x}.rpl", GHOST_PREFIX, hash)
+}
+
+impl Replay {
+    pub fn new(
+        kind: ReplayKind,
+        seed: u64,
+        settings: Settings,
+        score: u32,
+        frames: Vec<FrameInput>,
+    ) -> Self {
+        let recorded_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        Replay {
+            kind,
+            seed,
+            settings,
+            recorded_at,
+            score,
+            frames,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.dt).sum()
+    }
+
+    pub fn list() -> Vec<(String, Replay)> {
+        storage::list(REPLAY_PREFIX)
+            .into_iter()
+            .rev()
+            .filter_map(|name| {
+                let replay = Replay::load(&name)?;
+                Some((name, replay))
+            })
+            .collect()
+    }
+
+    pub fn load(name: &str) -> Option<Replay> {
+        Replay::decode(&storage::load_bytes(name)?)
+    }
+
+    pub fn save(&self) {
+        let name = format!("{}{:016}.rpl", REPLAY_PREFIX, self.recorded_at);
+        storage::save_bytes(&name, &self.encode());
+
+        let names = storage::list(REPLAY_PREFIX);
+        for old in names.iter().take(names.len().saturating_sub(MAX_REPLAYS)) {
+            storage::remove(old);
+        }
+    }
+
+    pub fn best(kind: &ReplayKind, settings: Settings, seed: u64) -> Option<Replay> {
+        Replay::load(&ghost_name(kind, settings, seed))
+    }
+
+    pub fn save_if_best(&self) {
+        let name = ghost_name(&self.kind, self.settings, self.seed);
+        let beaten = Replay::load(&name).is_none_or(|best| self.score > best.score);
+        if beaten {
+            storage::save_bytes(&name, &self.encode());
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64 + self.frames.len() * 14);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        let (kind, source) = self.kind.tag();
+        bytes.push(kind);
+        bytes.push(difficulty_index(self.settings));
+        bytes.push(self.settings.director as u8);
+        bytes.push(self.settings.mutators.bits());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.recorded_at.to_le_bytes());
+        bytes.extend_from_slice(&self.score.to_le_bytes());
+        bytes.extend_from_slice(&(source.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(source.as_bytes());
+        write_frames(&mut bytes, &self.frames);
+        bytes
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Replay> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return None;
+        }
+        let version = reader.u8()?;
+        if version == 0 || version > VERSION {
+            return None;
+        }
+        let kind = reader.u8()?;
+        let settings = Settings {
+            difficulty: *ALL_DIFFICULTIES.get(reader.u8()? as usize)?,
+            director: reader.u8()? != 0,
+            mutators: MutatorSet::from_bits(reader.u8()?),
+            ghost: false,
+            reduced_motion: false,
+        };
+        let seed = reader.u64()?;
+        let recorded_at = reader.u64()?;
+        let score = if version >= 2 { reader.u32()? } else { 0 };
+        let source_len = reader.u32()? as usize;
+        let source = String::from_utf8(reader.take(source_len)?.to_vec()).ok()?;
+        let kind = match kind {
+            0 => ReplayKind::Advanced,
+            1 => ReplayKind::Survival,
+            2 => ReplayKind::Level(source),
+            3 => ReplayKind::Daily,
+            4 => ReplayKind::Classic,
+            _ => return None,
+        };
+
+        let frames = read_frames(&mut reader)?;
+
+        Some(Replay {
+            kind,
+            seed,
+            settings,
+            recorded_at,
+            score,
+            frames,
+        })
+    }
+}
+
+fn write_frames(bytes: &mut Vec<u8>, frames: &[FrameInput]) {
+    bytes.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+    for frame in frames {
+        bytes.extend_from_slice(&(frame.dt.as_micros() as u32).to_le_bytes());
+        let mut flags = 0;
+        if frame.pointer.is_some() {
+            flags |= FLAG_POINTER;
+        }
+        if frame.click {
+            flags |= FLAG_CLICK;
+        }
+        if frame.reload {
+            flags |= FLAG_RELOAD;
+        }
+        bytes.push(flags);
+        if let Some(pos) = frame.pointer {
+            bytes.extend_from_slice(&pos.x.to_le_bytes());
+            bytes.extend_from_slice(&pos.y.to_le_bytes());
+        }
+        bytes.push(match frame.action {
+            None => ACTION_NONE,
+            Some(ShopAction::Buy(index)) => index as u8 + 1,
+            Some(ShopAction::NextWave) => ACTION_NEXT_WAVE,
+        });
+    }
+}
+
+fn read_frames(reader: &mut Reader) -> Option<Vec<FrameInput>> {
+    let count = reader.u32()? as usize;
+    let mut frames = Vec::with_capacity(count.min(reader.bytes.len() / 6));
+    for _ in 0..count {
+        let dt = Duration::from_micros(reader.u32()? as u64);
+        let flags = reader.u8()?;
+        let pointer = if flags & FLAG_POINTER != 0 {
+            Some(egui::pos2(reader.f32()?, reader.f32()?))
+        } else {
+            None
+        };
+        let action = match reader.u8()? {
+            ACTION_NONE => None,
+            ACTION_NEXT_WAVE => Some(ShopAction::NextWave),
+            index => Some(ShopAction::Buy(index as usize - 1)),
+        };
+        frames.push(FrameInput {
+            dt,
+            pointer,
+            click: flags & FLAG_CLICK != 0,
+            reload: flags & FLAG_RELOAD != 0,
+            action,
+        });
+    }
+    Some(frames)
+}
+
+pub fn pack_frames(frames: &[FrameInput]) -> String {
+    let mut bytes = Vec::with_capacity(4 + frames.len() * 14);
+    write_frames(&mut bytes, frames);
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn unpack_frames(text: &str) -> Option<Vec<FrameInput>> {
+    let bytes = (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    read_frames(&mut Reader {
+        bytes: &bytes,
+        pos: 0,
+    })
+}
+
+fn format_timestamp(millis: u64) -> String {
+    let secs = millis / 1000;
+    let (year, month, day) = daily::civil_date((secs / 86_400) as i64);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs % 86_400 / 3600,
+        secs % 3600 / 60
+    )
+}
+
+impl ReplayRound {
+    fn load(replay: &Replay) -> Result<ReplayRound, String> {
+        Ok(match replay.kind {
+            ReplayKind::Classic => ReplayRound::Classic(Box::new(ClassicApp::from_replay(replay))),
+            _ => ReplayRound::Advanced(Box::new(AdvancedApp::from_replay(replay)?)),
+        })
+    }
+
+    fn step(&mut self, input: &FrameInput, field: egui::Rect) {
+        match self {
+            ReplayRound::Classic(app) => {
+                app.step(input, field);
+            }
+            ReplayRound::Advanced(app) => {
+                app.step(input, field);
+            }
+        }
+    }
+
+    fn draw(&mut self, ctx: &egui::Context) {
+        match self {
+            ReplayRound::Classic(app) => {
+                app.draw(ctx);
+            }
+            ReplayRound::Advanced(app) => {
+                app.draw(ctx);
+            }
+        }
+    }
+
+    fn set_muted(&mut self, muted: bool) {
+        match self {
+            ReplayRound::Classic(app) => app.set_muted(muted),
+            ReplayRound::Advanced(app) => app.set_muted(muted),
+        }
+    }
+}
+
+impl Playback {
+    fn new(replay: Replay) -> Result<Playback, String> {
+        let app = ReplayRound::load(&replay)?;
+        let times = replay
+            .frames
+            .iter()
+            .scan(Duration::ZERO, |total, frame| {
+                *total += frame.dt;
+                Some(*total)
+            })
+            .collect();
+        Ok(Playback {
+            replay,
+            app,
+            times,
+            index: 0,
+            clock: Duration::ZERO,
+            playing: true,
+            speed: 1.0,
+        })
+    }
+
+    fn total(&self) -> Duration {
+        self.times.last().copied().unwrap_or_default()
+    }
+
+    fn run_until(&mut self, target: Duration, field: egui::Rect) {
+        while self.index < self.times.len() && self.times[self.index] <= target {
+            self.app.step(&self.replay.frames[self.index], field);
+            self.index += 1;
+        }
+        self.clock = target.min(self.total());
+    }
+
+    fn seek(&mut self, target: Duration, field: egui::Rect) {
+        if target < self.clock {
+            let Ok(app) = ReplayRound::load(&self.replay) else {
+                return;
+            };
+            self.app = app;
+            self.index = 0;
+            self.clock = Duration::ZERO;
+        }
+        self.app.set_muted(true);
+        self.run_until(target, field);
+    }
+
+    fn draw_controls(&mut self, ctx: &egui::Context) -> bool {
+        let mut back = false;
+        let field = ctx.screen_rect();
+        let total = self.total();
+
+        egui::TopBottomPanel::bottom("replay_controls").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("⏮").clicked() {
+                    self.seek(Duration::ZERO, field);
+                }
+                let label = if self.playing { "⏸" } else { "▶" };
+                if ui.button(label).clicked() {
+                    self.playing = !self.playing;
+                }
+                for speed in SPEEDS {
+                    if ui
+                        .selectable_label(self.speed == speed, format!("{}x", speed))
+                        .clicked()
+                    {
+                        self.speed = speed;
+                    }
+                }
+
+                let mut secs = self.clock.as_secs_f32();
+                let slider = ui
+                    .add(egui::Slider::new(&mut secs, 0.0..=total.as_secs_f32()).show_value(false));
+                if slider.changed() {
+                    self.seek(Duration::from_secs_f32(secs), field);
+                }
+                ui.label(format!(
+                    "{} / {}",
+                    survival::format_time(self.clock.as_secs_f32()),
+                    survival::format_time(total.as_secs_f32())
+                ));
+                if ui.button("Listeye Dön").clicked() {
+                    back = true;
+                }
+            });
+        });
+        back
+    }
+}
+
+impl ReplayViewer {
+    pub fn new() -> Self {
+        ReplayViewer {
+            screen: Screen::Select(Replay::list()),
+            last_frame: Instant::now(),
+        }
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_frame);
+        self.last_frame = now;
+        let mut signal = None;
+        let mut next = None;
+
+        match &mut self.screen {
+            Screen::Select(replays) => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(40.0);
+                        ui.heading(RichText::new("Tekrarlar").size(32.0));
+                        ui.label(
+                            RichText::new("Zamana Karşı, Antrenman ve Ritim turları kaydedilmez.")
+                                .size(16.0)
+                                .color(egui::Color32::GRAY),
+                        );
+                        ui.add_space(20.0);
+                        if replays.is_empty() {
+                            ui.label(RichText::new("Henüz kayıtlı tekrar yok.").size(18.0));
+                        }
+                        egui::ScrollArea::vertical()
+                            .max_height(400.0)
+                            .show(ui, |ui| {
+                                egui::Grid::new("replay_list").spacing([20.0, 6.0]).show(
+                                    ui,
+                                    |ui| {
+                                        for (index, (_, replay)) in replays.iter().enumerate() {
+                                            ui.label(format_timestamp(replay.recorded_at));
+                                            ui.label(replay.kind.name());
+                                            ui.label(replay.settings.difficulty.name());
+                                            ui.label(replay.settings.mutators.names());
+                                            ui.label(survival::format_time(
+                                                replay.duration().as_secs_f32(),
+                                            ));
+                                            if ui.button("İzle").clicked() {
+                                                next = Some(index);
+                                            }
+                                            ui.end_row();
+                                        }
+                                    },
+                                );
+                            });
+                        ui.add_space(20.0);
+                        if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                            signal = Some("menu".to_string());
+                        }
+                    });
+                });
+                if let Some(index) = next {
+                    let (_, replay) = replays.remove(index);
+                    self.screen = match Playback::new(replay) {
+                        Ok(playback) => Screen::Playing(Box::new(playback)),
+                        Err(error) => Screen::Error(error),
+                    };
+                }
+            }
+            Screen::Playing(playback) => {
+                let field = ctx.screen_rect();
+                let back = playback.draw_controls(ctx);
+                if playback.playing {
+                    let target = playback.clock + elapsed.mul_f32(playback.speed);
+                    playback.app.set_muted(playback.speed > 1.0);
+                    playback.run_until(target, field);
+                    if playback.index >= playback.times.len() {
+                        playback.playing = false;
+                    }
+                }
+                playback.app.draw(ctx);
+                ctx.request_repaint();
+                if back {
+                    self.screen = Screen::Select(Replay::list());
+                }
+            }
+            Screen::Error(error) => {
+                let error = error.clone();
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(150.0);
+                        ui.heading(RichText::new("Tekrar açılamadı").size(28.0));
+                        ui.label(error);
+                        ui.add_space(20.0);
+                        if ui.button(RichText::new("Listeye Dön").size(20.0)).clicked() {
+                            next = Some(0);
+                        }
+                    });
+                });
+                if next.is_some() {
+                    self.screen = Screen::Select(Replay::list());
+                }
+            }
+        }
+        signal
+    }
+}
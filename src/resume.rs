@@ -0,0 +1,49 @@
+use crate::advanced::AdvancedSnapshot;
+use crate::classic::ClassicSnapshot;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+const RESUME_FILE: &str = "resume.toml";
+
+#[derive(Serialize, Deserialize)]
+pub enum SavedRound {
+    Classic(Box<ClassicSnapshot>),
+    Advanced {
+        campaign_level: Option<usize>,
+        round: Box<AdvancedSnapshot>,
+    },
+}
+
+impl SavedRound {
+    pub fn exists() -> bool {
+        storage::save_path(RESUME_FILE).exists()
+    }
+
+    pub fn take() -> Option<SavedRound> {
+        let round = storage::read_toml(RESUME_FILE);
+        storage::remove(RESUME_FILE);
+        round
+    }
+
+    pub fn save(&self) {
+        storage::save_toml(RESUME_FILE, self);
+    }
+}
+
+pub fn remaining(t: Instant, now: Instant) -> f32 {
+    t.saturating_duration_since(now).as_secs_f32()
+}
+
+pub fn elapsed(t: Instant, now: Instant) -> f32 {
+    now.saturating_duration_since(t).as_secs_f32()
+}
+
+pub fn after(secs: f32, now: Instant) -> Instant {
+    now + Duration::from_secs_f32(secs.max(0.0))
+}
+
+pub fn ago(secs: f32, now: Instant) -> Instant {
+    now.checked_sub(Duration::from_secs_f32(secs.max(0.0)))
+        .unwrap_or(now)
+}
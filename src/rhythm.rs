@@ -0,0 +1,439 @@
+use crate::difficulty::Difficulty;
+use crate::music::{self, MusicPlayer};
+use crate::profile::{RoundSummary, RoundTally};
+use crate::storage;
+use eframe::egui;
+use egui::{Color32, RichText};
+use rand::Rng;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const RHYTHM_DIR: &str = "assets/rhythm";
+const APPROACH: f32 = 0.8;
+const PERFECT_WINDOW: f32 = 0.05;
+const GOOD_WINDOW: f32 = 0.12;
+const TARGET_RADIUS: f32 = 34.0;
+
+#[derive(Deserialize, Clone)]
+pub struct BeatMap {
+    pub title: String,
+    pub music: String,
+    #[serde(default)]
+    pub offset: f32,
+    #[serde(default)]
+    pub beats: Vec<f32>,
+    #[serde(default)]
+    pub detect: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct BeatCache {
+    #[serde(default)]
+    beats: Vec<f32>,
+}
+
+#[derive(Clone, Copy)]
+enum Grade {
+    Perfect,
+    Good,
+    Miss,
+}
+
+struct Note {
+    time: f32,
+    pos: egui::Pos2,
+    judged: Option<(Grade, f32)>,
+}
+
+struct Round {
+    title: String,
+    notes: Vec<Note>,
+    player: MusicPlayer,
+    score: u32,
+    combo: u32,
+    max_combo: u32,
+    counts: [u32; 3],
+    tally: RoundTally,
+}
+
+enum Screen {
+    Select(Vec<(PathBuf, String)>),
+    Playing(Box<Round>),
+    Results(Box<Round>),
+    Error(String),
+}
+
+pub struct RhythmApp {
+    screen: Screen,
+    summary: Option<RoundSummary>,
+    #[allow(dead_code)]
+    audio_stream: OutputStream,
+    audio_handle: OutputStreamHandle,
+}
+
+impl BeatMap {
+    pub fn load(path: &Path) -> Result<BeatMap, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("{} okunamadı: {}", path.display(), e))?;
+        let mut map: BeatMap = toml::from_str(&text)
+            .map_err(|e| format!("{} çözümlenemedi: {}", path.display(), e))?;
+
+        if map.detect && map.beats.is_empty() {
+            let stem = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let cache_name = format!("beatmap_{}.toml", stem);
+            let cache: BeatCache = storage::load_shared_toml(&cache_name);
+            map.beats = if cache.beats.is_empty() {
+                let beats = music::detect_onsets(&map.music)?;
+                storage::save_shared_toml(
+                    &cache_name,
+                    &BeatCache {
+                        beats: beats.clone(),
+                    },
+                );
+                beats
+            } else {
+                cache.beats
+            };
+        }
+
+        if map.beats.is_empty() {
+            return Err(format!("{} içinde vuruş bulunamadı", path.display()));
+        }
+        Ok(map)
+    }
+
+    pub fn list() -> Vec<(PathBuf, String)> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(RHYTHM_DIR)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| {
+                let title = std::fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|text| toml::from_str::<BeatMap>(&text).ok())
+                    .map(|map| map.title)
+                    .unwrap_or_else(|| path.display().to_string());
+                (path, title)
+            })
+            .collect()
+    }
+}
+
+impl Grade {
+    fn label(&self) -> &'static str {
+        match self {
+            Grade::Perfect => "Mükemmel",
+            Grade::Good => "İyi",
+            Grade::Miss => "Iska",
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self {
+            Grade::Perfect => Color32::GOLD,
+            Grade::Good => Color32::from_rgb(104, 198, 39),
+            Grade::Miss => Color32::RED,
+        }
+    }
+
+    fn points(&self) -> u32 {
+        match self {
+            Grade::Perfect => 300,
+            Grade::Good => 100,
+            Grade::Miss => 0,
+        }
+    }
+}
+
+impl Round {
+    fn start(map: BeatMap, handle: &OutputStreamHandle) -> Result<Round, String> {
+        let mut rng = rand::thread_rng();
+        let mut pos = egui::pos2(400.0, 330.0);
+        let mut beats = map.beats.clone();
+        beats.sort_by(f32::total_cmp);
+        let notes = beats
+            .into_iter()
+            .map(|beat| {
+                let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                let distance = rng.gen_range(120.0..220.0);
+                pos = egui::pos2(
+                    (pos.x + angle.cos() * distance).clamp(80.0, 720.0),
+                    (pos.y + angle.sin() * distance).clamp(140.0, 520.0),
+                );
+                Note {
+                    time: beat + map.offset,
+                    pos,
+                    judged: None,
+                }
+            })
+            .collect();
+
+        Ok(Round {
+            title: map.title,
+            notes,
+            player: MusicPlayer::play(handle, &map.music)?,
+            score: 0,
+            combo: 0,
+            max_combo: 0,
+            counts: [0; 3],
+            tally: RoundTally::default(),
+        })
+    }
+
+    fn judge(&mut self, index: usize, grade: Grade, at: f32) {
+        self.notes[index].judged = Some((grade, at));
+        self.counts[grade as usize] += 1;
+        self.score += grade.points() * (1 + self.combo / 10);
+        if let Grade::Miss = grade {
+            self.combo = 0;
+        } else {
+            self.combo += 1;
+            self.max_combo = self.max_combo.max(self.combo);
+        }
+    }
+
+    fn is_over(&self, position: f32) -> bool {
+        let last = self.notes.last().map_or(0.0, |note| note.time);
+        self.player.is_finished() || position > last + 1.5
+    }
+
+    fn accuracy(&self) -> f32 {
+        let possible = self.notes.len() as u32 * Grade::Perfect.points();
+        let earned = self.counts[Grade::Perfect as usize] * Grade::Perfect.points()
+            + self.counts[Grade::Good as usize] * Grade::Good.points();
+        earned as f32 / possible.max(1) as f32
+    }
+}
+
+impl RhythmApp {
+    pub fn new() -> Self {
+        let (stream, stream_handle) = OutputStream::try_default().unwrap();
+        Self {
+            screen: Screen::Select(BeatMap::list()),
+            summary: None,
+            audio_stream: stream,
+            audio_handle: stream_handle,
+        }
+    }
+
+    pub fn take_summary(&mut self) -> Option<RoundSummary> {
+        self.summary.take()
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
+        let mut signal = None;
+        let click = ctx.input(|i| {
+            if i.pointer.primary_clicked() {
+                i.pointer.interact_pos()
+            } else {
+                None
+            }
+        });
+
+        let mut next_screen = None;
+        let playing = matches!(self.screen, Screen::Playing(_));
+        egui::CentralPanel::default().show(ctx, |ui| match &mut self.screen {
+            Screen::Select(maps) => {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(60.0);
+                    ui.heading(RichText::new("Ritim").size(36.0));
+                    ui.add_space(30.0);
+                    if maps.is_empty() {
+                        ui.label(RichText::new("Ritim haritası bulunamadı.").size(18.0));
+                    }
+                    for (path, title) in maps.iter() {
+                        let button = ui.add_sized(
+                            [280.0, 40.0],
+                            egui::Button::new(RichText::new(title).size(20.0)),
+                        );
+                        if button.clicked() {
+                            next_screen = Some(
+                                BeatMap::load(path)
+                                    .and_then(|map| Round::start(map, &self.audio_handle))
+                                    .map_or_else(Screen::Error, |round| {
+                                        Screen::Playing(Box::new(round))
+                                    }),
+                            );
+                        }
+                        ui.add_space(8.0);
+                    }
+                    ui.add_space(30.0);
+                    if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                        signal = Some("menu".to_string());
+                    }
+                });
+            }
+            Screen::Playing(round) => {
+                let position = round.player.position().as_secs_f32();
+
+                for index in 0..round.notes.len() {
+                    let note = &round.notes[index];
+                    if note.judged.is_none() && position > note.time + GOOD_WINDOW {
+                        round.judge(index, Grade::Miss, position);
+                    }
+                }
+
+                if let Some(pos) = click {
+                    let target = round.notes.iter().position(|note| {
+                        note.judged.is_none()
+                            && note.time - position <= APPROACH
+                            && note.pos.distance(pos) <= TARGET_RADIUS
+                    });
+                    if let Some(index) = target {
+                        let error = (position - round.notes[index].time).abs();
+                        let grade = if error <= PERFECT_WINDOW {
+                            Grade::Perfect
+                        } else if error <= GOOD_WINDOW {
+                            Grade::Good
+                        } else {
+                            Grade::Miss
+                        };
+                        round.judge(index, grade, position);
+                    }
+                    let hit = target.is_some_and(|index| {
+                        !matches!(round.notes[index].judged, Some((Grade::Miss, _)))
+                    });
+                    round.tally.shot(hit);
+                    round.tally.heat.shot(pos, hit);
+                }
+
+                ui.label(RichText::new(&round.title).size(16.0));
+                ui.label(RichText::new(format!("Puan: {}", round.score)).size(20.0));
+                ui.label(RichText::new(format!("Kombo: {}", round.combo)).size(20.0));
+
+                for note in round.notes.iter().rev() {
+                    let until = note.time - position;
+                    match note.judged {
+                        None if until <= APPROACH => {
+                            let progress = (1.0 - until / APPROACH).clamp(0.0, 1.0);
+                            let painter = ui.painter();
+                            painter.circle_filled(
+                                note.pos,
+                                TARGET_RADIUS,
+                                Color32::from_rgb(220, 40, 40),
+                            );
+                            painter.circle_filled(note.pos, TARGET_RADIUS * 0.6, Color32::WHITE);
+                            painter.circle_stroke(
+                                note.pos,
+                                TARGET_RADIUS * (3.0 - 2.0 * progress),
+                                (3.0, Color32::from_rgb(60, 120, 220)),
+                            );
+                        }
+                        Some((grade, at)) if position - at < 0.4 => {
+                            ui.painter().text(
+                                note.pos,
+                                egui::Align2::CENTER_CENTER,
+                                grade.label(),
+                                egui::FontId::proportional(22.0),
+                                grade.color(),
+                            );
+                        }
+                        _ => {}
+                    }
+                }
+
+                if round.is_over(position)
+                    && let Screen::Playing(round) =
+                        std::mem::replace(&mut self.screen, Screen::Select(Vec::new()))
+                {
+                    self.summary = Some(RoundSummary {
+                        mode: "Ritim".to_string(),
+                        difficulty: Difficulty::default(),
+                        score: round.score,
+                        secs: position,
+                        timed: false,
+                        tally: round.tally.clone(),
+                    });
+                    next_screen = Some(Screen::Results(round));
+                }
+            }
+            Screen::Results(round) => {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(80.0);
+                    ui.heading(RichText::new(format!("{} - Sonuç", round.title)).size(32.0));
+                    ui.add_space(20.0);
+                    ui.label(RichText::new(format!("Puan: {}", round.score)).size(24.0));
+                    ui.label(
+                        RichText::new(format!("Doğruluk: %{:.1}", round.accuracy() * 100.0))
+                            .size(20.0),
+                    );
+                    ui.label(
+                        RichText::new(format!("En Uzun Kombo: {}", round.max_combo)).size(20.0),
+                    );
+                    ui.add_space(10.0);
+                    for grade in [Grade::Perfect, Grade::Good, Grade::Miss] {
+                        ui.label(
+                            RichText::new(format!(
+                                "{}: {}",
+                                grade.label(),
+                                round.counts[grade as usize]
+                            ))
+                            .size(18.0)
+                            .color(grade.color()),
+                        );
+                    }
+                    ui.add_space(20.0);
+                    if ui.button(RichText::new("Parça Seç").size(20.0)).clicked() {
+                        next_screen = Some(Screen::Select(BeatMap::list()));
+                    }
+                    ui.add_space(10.0);
+                    if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                        signal = Some("menu".to_string());
+                    }
+                });
+            }
+            Screen::Error(message) => {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(150.0);
+                    ui.label(
+                        RichText::new(message.as_str())
+                            .size(20.0)
+                            .color(Color32::RED),
+                    );
+                    ui.add_space(20.0);
+                    if ui.button(RichText::new("Geri").size(20.0)).clicked() {
+                        next_screen = Some(Screen::Select(BeatMap::list()));
+                    }
+                });
+            }
+        });
+
+        if playing && click.is_some() {
+            self.play_sound("assets/sound/gunshot.mp3");
+        }
+
+        if let Some(screen) = next_screen {
+            self.screen = screen;
+        }
+
+        if let Screen::Playing(_) = self.screen {
+            ctx.request_repaint();
+        } else {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        signal
+    }
+
+    fn play_sound(&self, path: &str) {
+        if let Ok(file) = std::fs::File::open(path)
+            && let Ok(source) = Decoder::new(BufReader::new(file))
+            && let Ok(sink) = Sink::try_new(&self.audio_handle)
+        {
+            sink.append(source);
+            sink.detach();
+        }
+    }
+}
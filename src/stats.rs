@@ -0,0 +1,410 @@
+use crate::daily;
+use crate::difficulty::{ALL_DIFFICULTIES, Difficulty};
+use crate::profile::RoundSummary;
+use crate::storage;
+use egui::{Color32, Context, RichText};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOG_FILE: &str = "rounds.toml";
+const MAX_ROUNDS: usize = 500;
+const REACTION_BIN_MS: u32 = 100;
+const REACTION_BINS: usize = 20;
+const CHART_SIZE: egui::Vec2 = egui::vec2(370.0, 190.0);
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct RoundLog {
+    #[serde(default)]
+    pub rounds: Vec<RoundRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundRecord {
+    pub mode: String,
+    pub difficulty: Difficulty,
+    pub score: u32,
+    pub secs: f32,
+    pub shots: u32,
+    pub hits: u32,
+    pub played_at: u64,
+    #[serde(default)]
+    pub reactions: Vec<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DateRange {
+    All,
+    Today,
+    Week,
+    Month,
+}
+
+const ALL_RANGES: [DateRange; 4] = [
+    DateRange::All,
+    DateRange::Today,
+    DateRange::Week,
+    DateRange::Month,
+];
+
+pub struct StatsScreen {
+    log: RoundLog,
+    mode: Option<String>,
+    difficulty: Option<Difficulty>,
+    range: DateRange,
+}
+
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+impl RoundLog {
+    pub fn load() -> RoundLog {
+        storage::load_toml(LOG_FILE)
+    }
+
+    pub fn record(&mut self, summary: &RoundSummary) {
+        self.rounds.push(RoundRecord {
+            mode: summary.mode.clone(),
+            difficulty: summary.difficulty,
+            score: summary.score,
+            secs: summary.secs,
+            shots: summary.tally.shots,
+            hits: summary.tally.hits,
+            played_at: unix_secs(),
+            reactions: summary.tally.reactions.clone(),
+        });
+        if self.rounds.len() > MAX_ROUNDS {
+            self.rounds.drain(..self.rounds.len() - MAX_ROUNDS);
+        }
+        storage::save_toml(LOG_FILE, self);
+    }
+}
+
+impl RoundRecord {
+    fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots as f32
+        }
+    }
+
+    fn date(&self) -> String {
+        let (year, month, day) = daily::civil_date((self.played_at / 86_400) as i64);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl DateRange {
+    fn name(&self) -> &'static str {
+        match self {
+            DateRange::All => "Tüm zamanlar",
+            DateRange::Today => "Bugün",
+            DateRange::Week => "Son 7 gün",
+            DateRange::Month => "Son 30 gün",
+        }
+    }
+
+    fn contains(&self, played_at: u64, now: u64) -> bool {
+        let days = now / 86_400 - (played_at / 86_400).min(now / 86_400);
+        match self {
+            DateRange::All => true,
+            DateRange::Today => days == 0,
+            DateRange::Week => days < 7,
+            DateRange::Month => days < 30,
+        }
+    }
+}
+
+impl StatsScreen {
+    pub fn new() -> Self {
+        StatsScreen {
+            log: RoundLog::load(),
+            mode: None,
+            difficulty: None,
+            range: DateRange::All,
+        }
+    }
+
+    fn filtered(&self) -> Vec<&RoundRecord> {
+        let now = unix_secs();
+        self.log
+            .rounds
+            .iter()
+            .filter(|round| self.mode.as_ref().is_none_or(|mode| &round.mode == mode))
+            .filter(|round| {
+                self.difficulty
+                    .is_none_or(|difficulty| round.difficulty == difficulty)
+            })
+            .filter(|round| self.range.contains(round.played_at, now))
+            .collect()
+    }
+
+    pub fn update(&mut self, ctx: &Context) -> Option<String> {
+        let mut signal = None;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(10.0);
+                ui.heading(RichText::new("İstatistikler").size(32.0));
+            });
+            ui.add_space(10.0);
+            self.draw_filters(ui);
+            ui.add_space(10.0);
+
+            let rounds = self.filtered();
+            if rounds.is_empty() {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(150.0);
+                    ui.label(RichText::new("Bu filtreyle eşleşen tur yok.").size(18.0));
+                });
+            } else {
+                let scores: Vec<f32> = rounds.iter().map(|round| round.score as f32).collect();
+                let best = scores.iter().copied().fold(1.0, f32::max);
+                let accuracy: Vec<f32> = rounds
+                    .iter()
+                    .map(|round| round.accuracy() * 100.0)
+                    .collect();
+                let mut bins = [0.0; REACTION_BINS];
+                for reaction in rounds.iter().flat_map(|round| &round.reactions) {
+                    let bin = (reaction / REACTION_BIN_MS) as usize;
+                    bins[bin.min(REACTION_BINS - 1)] += 1.0;
+                }
+                let mut modes: Vec<(String, f32)> = Vec::new();
+                let mut counts: Vec<u32> = Vec::new();
+                for round in &rounds {
+                    match modes.iter().position(|(mode, _)| mode == &round.mode) {
+                        Some(index) => {
+                            modes[index].1 += round.score as f32;
+                            counts[index] += 1;
+                        }
+                        None => {
+                            modes.push((round.mode.clone(), round.score as f32));
+                            counts.push(1);
+                        }
+                    }
+                }
+                for ((mode, total), count) in modes.iter_mut().zip(&counts) {
+                    *total /= *count as f32;
+                    *mode = format!("{} ({})", mode, count);
+                }
+
+                let first = rounds.first().map(|round| round.date()).unwrap_or_default();
+                let last = rounds.last().map(|round| round.date()).unwrap_or_default();
+                ui.label(
+                    RichText::new(format!("{} tur, {} - {}", rounds.len(), first, last))
+                        .color(Color32::GRAY),
+                );
+
+                ui.horizontal(|ui| {
+                    line_chart(
+                        ui,
+                        "Tur Başına Puan",
+                        &scores,
+                        best,
+                        Color32::from_rgb(0, 120, 215),
+                    );
+                    line_chart(
+                        ui,
+                        "İsabet Oranı (%)",
+                        &accuracy,
+                        100.0,
+                        Color32::from_rgb(0, 160, 0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    let labels: Vec<(String, f32)> = bins
+                        .iter()
+                        .enumerate()
+                        .map(|(i, count)| ((i as u32 * REACTION_BIN_MS).to_string(), *count))
+                        .collect();
+                    column_chart(ui, "Tepki Süresi (ms)", &labels);
+                    bar_chart(ui, "Mod Başına Ortalama Puan", &modes);
+                });
+            }
+
+            ui.vertical_centered(|ui| {
+                ui.add_space(10.0);
+                if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                    signal = Some("menu".to_string());
+                }
+            });
+        });
+
+        signal
+    }
+
+    fn draw_filters(&mut self, ui: &mut egui::Ui) {
+        let mut modes: Vec<String> = self
+            .log
+            .rounds
+            .iter()
+            .map(|round| round.mode.clone())
+            .collect();
+        modes.sort();
+        modes.dedup();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("stats_mode")
+                .selected_text(self.mode.as_deref().unwrap_or("Tüm modlar"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.mode, None, "Tüm modlar");
+                    for mode in modes {
+                        let label = mode.clone();
+                        ui.selectable_value(&mut self.mode, Some(mode), label);
+                    }
+                });
+            egui::ComboBox::from_id_source("stats_difficulty")
+                .selected_text(self.difficulty.map_or("Tüm zorluklar", |d| d.name()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.difficulty, None, "Tüm zorluklar");
+                    for difficulty in ALL_DIFFICULTIES {
+                        ui.selectable_value(
+                            &mut self.difficulty,
+                            Some(difficulty),
+                            difficulty.name(),
+                        );
+                    }
+                });
+            egui::ComboBox::from_id_source("stats_range")
+                .selected_text(self.range.name())
+                .show_ui(ui, |ui| {
+                    for range in ALL_RANGES {
+                        ui.selectable_value(&mut self.range, range, range.name());
+                    }
+                });
+        });
+    }
+}
+
+fn chart_frame(ui: &mut egui::Ui, title: &str) -> (egui::Painter, egui::Rect) {
+    let (rect, _) = ui.allocate_exact_size(CHART_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 4.0, (1.0, Color32::from_gray(160)));
+    painter.text(
+        rect.left_top() + egui::vec2(8.0, 4.0),
+        egui::Align2::LEFT_TOP,
+        title,
+        egui::FontId::proportional(14.0),
+        ui.visuals().text_color(),
+    );
+    let plot = egui::Rect::from_min_max(
+        rect.left_top() + egui::vec2(40.0, 26.0),
+        rect.right_bottom() - egui::vec2(10.0, 20.0),
+    );
+    (painter, plot)
+}
+
+fn axis_label(painter: &egui::Painter, pos: egui::Pos2, align: egui::Align2, text: String) {
+    painter.text(
+        pos,
+        align,
+        text,
+        egui::FontId::proportional(11.0),
+        Color32::GRAY,
+    );
+}
+
+fn line_chart(ui: &mut egui::Ui, title: &str, values: &[f32], max: f32, color: Color32) {
+    let (painter, plot) = chart_frame(ui, title);
+    axis_label(
+        &painter,
+        plot.left_top() - egui::vec2(4.0, 0.0),
+        egui::Align2::RIGHT_TOP,
+        format!("{:.0}", max),
+    );
+    axis_label(
+        &painter,
+        plot.left_bottom() - egui::vec2(4.0, 0.0),
+        egui::Align2::RIGHT_BOTTOM,
+        "0".to_string(),
+    );
+    axis_label(
+        &painter,
+        plot.right_bottom() + egui::vec2(0.0, 2.0),
+        egui::Align2::RIGHT_TOP,
+        format!("{} tur", values.len()),
+    );
+    painter.line_segment(
+        [plot.left_bottom(), plot.right_bottom()],
+        (1.0, Color32::GRAY),
+    );
+
+    let step = plot.width() / (values.len().max(2) - 1) as f32;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            egui::pos2(
+                plot.left() + step * i as f32,
+                plot.bottom() - plot.height() * value / max,
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(points.clone(), (2.0, color)));
+    if points.len() <= 60 {
+        for point in points {
+            painter.circle_filled(point, 2.5, color);
+        }
+    }
+}
+
+fn column_chart(ui: &mut egui::Ui, title: &str, columns: &[(String, f32)]) {
+    let (painter, plot) = chart_frame(ui, title);
+    let max = columns.iter().map(|(_, value)| *value).fold(1.0, f32::max);
+    axis_label(
+        &painter,
+        plot.left_top() - egui::vec2(4.0, 0.0),
+        egui::Align2::RIGHT_TOP,
+        format!("{:.0}", max),
+    );
+    let width = plot.width() / columns.len().max(1) as f32;
+    for (i, (label, value)) in columns.iter().enumerate() {
+        let left = plot.left() + width * i as f32;
+        let height = plot.height() * value / max;
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(left + 1.0, plot.bottom() - height),
+                egui::pos2(left + width - 1.0, plot.bottom()),
+            ),
+            0.0,
+            Color32::from_rgb(255, 140, 0),
+        );
+        if i % 5 == 0 {
+            axis_label(
+                &painter,
+                egui::pos2(left, plot.bottom() + 2.0),
+                egui::Align2::LEFT_TOP,
+                label.clone(),
+            );
+        }
+    }
+}
+
+fn bar_chart(ui: &mut egui::Ui, title: &str, bars: &[(String, f32)]) {
+    let (painter, plot) = chart_frame(ui, title);
+    let plot = plot.with_min_x(plot.left() - 30.0);
+    let max = bars.iter().map(|(_, value)| *value).fold(1.0, f32::max);
+    let height = (plot.height() / bars.len().max(1) as f32).min(24.0);
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let top = plot.top() + height * i as f32;
+        let width = plot.width() * value / max;
+        painter.rect_filled(
+            egui::Rect::from_min_size(
+                egui::pos2(plot.left(), top + 2.0),
+                egui::vec2(width, height - 4.0),
+            ),
+            2.0,
+            Color32::from_rgb(120, 80, 200),
+        );
+        painter.text(
+            egui::pos2(plot.left() + 4.0, top + height / 2.0),
+            egui::Align2::LEFT_CENTER,
+            format!("{}: {:.0}", label, value),
+            egui::FontId::proportional(12.0),
+            ui.visuals().strong_text_color(),
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const SAVE_DIR: &str = "save";
+
+static PROFILE_DIR: RwLock<String> = RwLock::new(String::new());
+
+pub fn set_profile_dir(dir: &str) {
+    if let Ok(mut current) = PROFILE_DIR.write() {
+        *current = dir.to_string();
+    }
+}
+
+pub fn save_dir() -> PathBuf {
+    let dir = PROFILE_DIR
+        .read()
+        .map(|dir| dir.clone())
+        .unwrap_or_default();
+    PathBuf::from(SAVE_DIR).join(dir)
+}
+
+pub fn save_path(name: &str) -> PathBuf {
+    save_dir().join(name)
+}
+
+pub fn load_toml<T: DeserializeOwned + Default>(name: &str) -> T {
+    read_toml(name).unwrap_or_default()
+}
+
+pub fn read_toml<T: DeserializeOwned>(name: &str) -> Option<T> {
+    read_toml_at(&save_path(name))
+}
+
+pub fn save_toml<T: Serialize>(name: &str, value: &T) {
+    write_toml_at(&save_dir(), name, value);
+}
+
+pub fn load_shared_toml<T: DeserializeOwned + Default>(name: &str) -> T {
+    read_toml_at(&PathBuf::from(SAVE_DIR).join(name)).unwrap_or_default()
+}
+
+pub fn save_shared_toml<T: Serialize>(name: &str, value: &T) {
+    write_toml_at(Path::new(SAVE_DIR), name, value);
+}
+
+fn read_toml_at<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+}
+
+fn write_toml_at<T: Serialize>(dir: &Path, name: &str, value: &T) {
+    let Ok(text) = toml::to_string(value) else {
+        return;
+    };
+    if std::fs::create_dir_all(dir).is_ok() {
+        let _ = std::fs::write(dir.join(name), text);
+    }
+}
+
+pub fn save_bytes(name: &str, bytes: &[u8]) {
+    let dir = save_dir();
+    if std::fs::create_dir_all(&dir).is_ok() {
+        let _ = std::fs::write(dir.join(name), bytes);
+    }
+}
+
+pub fn load_bytes(name: &str) -> Option<Vec<u8>> {
+    std::fs::read(save_path(name)).ok()
+}
+
+pub fn remove(name: &str) {
+    let _ = std::fs::remove_file(save_path(name));
+}
+
+pub fn list(prefix: &str) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(save_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with(prefix))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
@@ -0,0 +1,153 @@
+use eframe::egui;
+use rand::Rng;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SupplyBoxType {
+    Health,
+    Tnt,
+    SlowMotion,
+    Freeze,
+    DoublePoints,
+    Ammo,
+    Shield,
+}
+
+pub struct SupplySpec {
+    pub name: &'static str,
+    pub sprite: &'static str,
+    pub sounds: &'static [&'static str],
+    pub duration: Option<Duration>,
+    pub hitpoints: u32,
+    pub color: egui::Color32,
+}
+
+const CATALOG: [SupplySpec; 7] = [
+    SupplySpec {
+        name: "Sağlık",
+        sprite: "assets/sprite/supplybox_health.png",
+        sounds: &[
+            "assets/sound/supplybox_damage-1.mp3",
+            "assets/sound/supplybox_damage-2.mp3",
+            "assets/sound/supplybox_damage-3.mp3",
+        ],
+        duration: None,
+        hitpoints: 2,
+        color: egui::Color32::from_rgb(104, 198, 39),
+    },
+    SupplySpec {
+        name: "TNT",
+        sprite: "assets/sprite/supplybox_tnt.png",
+        sounds: &["assets/sound/supplybox_explosion.mp3"],
+        duration: None,
+        hitpoints: 1,
+        color: egui::Color32::from_rgb(255, 140, 0),
+    },
+    SupplySpec {
+        name: "Ağır Çekim",
+        sprite: "assets/sprite/supplybox_slowmo.png",
+        sounds: &["assets/sound/supplybox_slowmo.wav"],
+        duration: Some(Duration::from_secs(5)),
+        hitpoints: 2,
+        color: egui::Color32::from_rgb(167, 123, 214),
+    },
+    SupplySpec {
+        name: "Dondurma",
+        sprite: "assets/sprite/supplybox_freeze.png",
+        sounds: &["assets/sound/supplybox_freeze.wav"],
+        duration: Some(Duration::from_secs(4)),
+        hitpoints: 2,
+        color: egui::Color32::from_rgb(159, 227, 245),
+    },
+    SupplySpec {
+        name: "Çift Puan",
+        sprite: "assets/sprite/supplybox_double.png",
+        sounds: &["assets/sound/supplybox_double.wav"],
+        duration: Some(Duration::from_secs(8)),
+        hitpoints: 2,
+        color: egui::Color32::GOLD,
+    },
+    SupplySpec {
+        name: "Mermi",
+        sprite: "assets/sprite/supplybox_ammo.png",
+        sounds: &["assets/sound/supplybox_ammo.wav"],
+        duration: None,
+        hitpoints: 1,
+        color: egui::Color32::from_rgb(224, 176, 64),
+    },
+    SupplySpec {
+        name: "Kalkan",
+        sprite: "assets/sprite/supplybox_shield.png",
+        sounds: &["assets/sound/supplybox_shield.wav"],
+        duration: Some(Duration::from_secs(6)),
+        hitpoints: 3,
+        color: egui::Color32::from_rgb(111, 155, 242),
+    },
+];
+
+pub type LootTable = &'static [(SupplyBoxType, u32)];
+
+pub const COMMON_LOOT: LootTable = &[
+    (SupplyBoxType::Health, 50),
+    (SupplyBoxType::Ammo, 30),
+    (SupplyBoxType::DoublePoints, 20),
+];
+
+pub const ELITE_LOOT: LootTable = &[
+    (SupplyBoxType::Health, 25),
+    (SupplyBoxType::Tnt, 15),
+    (SupplyBoxType::SlowMotion, 15),
+    (SupplyBoxType::Freeze, 15),
+    (SupplyBoxType::DoublePoints, 15),
+    (SupplyBoxType::Shield, 15),
+];
+
+const DROP_TABLES: [LootTable; 3] = [
+    &[
+        (SupplyBoxType::Health, 45),
+        (SupplyBoxType::Tnt, 20),
+        (SupplyBoxType::Ammo, 15),
+        (SupplyBoxType::DoublePoints, 10),
+        (SupplyBoxType::SlowMotion, 5),
+        (SupplyBoxType::Shield, 5),
+    ],
+    &[
+        (SupplyBoxType::Health, 35),
+        (SupplyBoxType::Tnt, 20),
+        (SupplyBoxType::Ammo, 12),
+        (SupplyBoxType::DoublePoints, 10),
+        (SupplyBoxType::SlowMotion, 8),
+        (SupplyBoxType::Freeze, 8),
+        (SupplyBoxType::Shield, 7),
+    ],
+    &[
+        (SupplyBoxType::Health, 25),
+        (SupplyBoxType::Tnt, 25),
+        (SupplyBoxType::Ammo, 10),
+        (SupplyBoxType::DoublePoints, 10),
+        (SupplyBoxType::SlowMotion, 10),
+        (SupplyBoxType::Freeze, 10),
+        (SupplyBoxType::Shield, 10),
+    ],
+];
+
+impl SupplyBoxType {
+    pub fn spec(self) -> &'static SupplySpec {
+        &CATALOG[self as usize]
+    }
+
+    pub fn sprites() -> impl Iterator<Item = &'static str> {
+        CATALOG.iter().map(|spec| spec.sprite)
+    }
+
+    pub fn roll(tier: usize, rng: &mut impl Rng) -> SupplyBoxType {
+        Self::roll_from(DROP_TABLES[tier.min(DROP_TABLES.len() - 1)], rng)
+    }
+
+    pub fn roll_from(table: LootTable, rng: &mut impl Rng) -> SupplyBoxType {
+        let weights = WeightedIndex::new(table.iter().map(|(_, weight)| *weight)).unwrap();
+        table[weights.sample(rng)].0
+    }
+}
@@ -0,0 +1,95 @@
+use crate::difficulty::Difficulty;
+use crate::storage;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+const LEADERBOARD_FILE: &str = "survival.toml";
+const LEADERBOARD_SIZE: usize = 10;
+
+pub struct ThreatLevel {
+    pub name: &'static str,
+    pub from_secs: f32,
+    pub color: egui::Color32,
+}
+
+pub const THREAT_LEVELS: [ThreatLevel; 5] = [
+    ThreatLevel {
+        name: "Sakin",
+        from_secs: 0.0,
+        color: egui::Color32::from_rgb(104, 198, 39),
+    },
+    ThreatLevel {
+        name: "Gergin",
+        from_secs: 30.0,
+        color: egui::Color32::from_rgb(220, 200, 40),
+    },
+    ThreatLevel {
+        name: "Tehlikeli",
+        from_secs: 60.0,
+        color: egui::Color32::from_rgb(255, 140, 0),
+    },
+    ThreatLevel {
+        name: "Kritik",
+        from_secs: 100.0,
+        color: egui::Color32::from_rgb(230, 50, 30),
+    },
+    ThreatLevel {
+        name: "Kıyamet",
+        from_secs: 150.0,
+        color: egui::Color32::from_rgb(160, 0, 160),
+    },
+];
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct SurvivalBoard {
+    #[serde(default)]
+    pub entries: Vec<SurvivalEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SurvivalEntry {
+    pub secs: f32,
+    pub score: u32,
+    pub difficulty: Difficulty,
+}
+
+pub fn threat_level(secs: f32) -> &'static ThreatLevel {
+    THREAT_LEVELS
+        .iter()
+        .rev()
+        .find(|level| secs >= level.from_secs)
+        .unwrap_or(&THREAT_LEVELS[0])
+}
+
+pub fn intensity(secs: f32) -> f32 {
+    1.0 + (secs / 45.0).powf(0.8)
+}
+
+pub fn elite_share(secs: f32) -> f64 {
+    ((secs as f64 - 20.0) / 200.0).clamp(0.0, 0.35)
+}
+
+pub fn format_time(secs: f32) -> String {
+    format!("{}:{:04.1}", (secs / 60.0) as u32, secs % 60.0)
+}
+
+impl SurvivalBoard {
+    pub fn load() -> SurvivalBoard {
+        storage::load_toml(LEADERBOARD_FILE)
+    }
+
+    pub fn record(&mut self, entry: SurvivalEntry) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| entry.secs > other.secs)
+            .unwrap_or(self.entries.len());
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+        storage::save_toml(LEADERBOARD_FILE, self);
+        Some(rank)
+    }
+}
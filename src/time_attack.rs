@@ -0,0 +1,448 @@
+use crate::advanced::EnemyType;
+use crate::difficulty::Difficulty;
+use crate::profile::{RoundSummary, RoundTally};
+use crate::storage;
+use eframe::egui;
+use egui::{RichText, TextureHandle, TextureOptions};
+use image::GenericImageView;
+use image::io::Reader as ImageReader;
+use rand::Rng;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufReader;
+use std::time::{Duration, Instant};
+
+const TARGET_COUNT: usize = 20;
+const MAX_ON_SCREEN: usize = 3;
+const MISS_PENALTY: Duration = Duration::from_secs(1);
+const RECORD_FILE: &str = "time_attack.toml";
+
+pub struct TimeAttackApp {
+    targets: Vec<Target>,
+    spawned: usize,
+    splits: Vec<Duration>,
+    misses: u32,
+    textures: HashMap<String, TextureHandle>,
+    start_secs: f64,
+    finish_time: Option<Instant>,
+    last_miss: Option<Instant>,
+    show_intro: bool,
+    intro_start: Instant,
+    record: TimeAttackRecord,
+    new_record: bool,
+    summary_taken: bool,
+    tally: RoundTally,
+    #[allow(dead_code)]
+    audio_stream: OutputStream,
+    audio_handle: OutputStreamHandle,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct TimeAttackRecord {
+    #[serde(default)]
+    best_splits_ms: Vec<u64>,
+}
+
+enum TargetState {
+    Alive,
+    Dying(Instant),
+}
+
+struct Target {
+    x: f32,
+    y: f32,
+    texture_key: String,
+    state: TargetState,
+}
+
+impl TimeAttackApp {
+    pub fn new() -> Self {
+        let (stream, stream_handle) = OutputStream::try_default().unwrap();
+        Self {
+            targets: Vec::new(),
+            spawned: 0,
+            splits: Vec::new(),
+            misses: 0,
+            textures: HashMap::new(),
+            start_secs: 0.0,
+            finish_time: None,
+            last_miss: None,
+            show_intro: true,
+            intro_start: Instant::now(),
+            record: storage::load_toml(RECORD_FILE),
+            new_record: false,
+            summary_taken: false,
+            tally: RoundTally::default(),
+            audio_stream: stream,
+            audio_handle: stream_handle,
+        }
+    }
+
+    pub fn take_summary(&mut self) -> Option<RoundSummary> {
+        if self.finish_time.is_none() || self.summary_taken {
+            return None;
+        }
+        self.summary_taken = true;
+        let total = self.splits.last().copied().unwrap_or_default();
+        Some(RoundSummary {
+            mode: "Zamana Karşı".to_string(),
+            difficulty: Difficulty::default(),
+            score: self.splits.len() as u32,
+            secs: total.as_secs_f32(),
+            timed: true,
+            tally: self.tally.clone(),
+        })
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
+        if self.finish_time.is_none() && ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            return Some("menu".to_string());
+        }
+        let now = Instant::now();
+        let time = ctx.input(|i| i.time);
+        let mut signal = None;
+
+        for name in [
+            "assets/sprite/enemy-1.png",
+            "assets/sprite/enemy-2.png",
+            "assets/sprite/enemy_death-1.png",
+            "assets/sprite/enemy_death-2.png",
+        ] {
+            if !self.textures.contains_key(name) {
+                let texture = load_image(ctx, name);
+                self.textures.insert(name.to_string(), texture);
+            }
+        }
+
+        let display_width = 75.0;
+        let display_height = display_width * (55.0 / 35.0);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.show_intro {
+                let elapsed_intro = now.duration_since(self.intro_start);
+                ui.vertical_centered(|ui| {
+                    ui.add_space(200.0);
+                    if elapsed_intro < Duration::from_secs(2) {
+                        ui.heading(
+                            RichText::new(format!("{} hedef. Hazır mısın?", TARGET_COUNT))
+                                .size(36.0),
+                        );
+                    } else if elapsed_intro < Duration::from_secs(4) {
+                        ui.heading(RichText::new("Başla!").size(36.0));
+                    } else {
+                        self.start_secs = time;
+                        self.show_intro = false;
+                    }
+                    ui.add_space(20.0);
+                    ui.label(
+                        RichText::new("Esc: menüye dön")
+                            .size(16.0)
+                            .color(egui::Color32::GRAY),
+                    );
+                });
+                return;
+            }
+
+            if self.finish_time.is_some() {
+                self.draw_results(ui, &mut signal);
+                return;
+            }
+
+            self.targets.retain(|target| match target.state {
+                TargetState::Alive => true,
+                TargetState::Dying(t0) => now.duration_since(t0) < Duration::from_millis(500),
+            });
+
+            let alive = self
+                .targets
+                .iter()
+                .filter(|target| matches!(target.state, TargetState::Alive))
+                .count();
+            if alive == 0 && self.spawned < TARGET_COUNT {
+                let batch = rand::thread_rng()
+                    .gen_range(1..=MAX_ON_SCREEN)
+                    .min(TARGET_COUNT - self.spawned);
+                for _ in 0..batch {
+                    self.spawn_target();
+                }
+            }
+
+            ui.label(RichText::new(format!("Süre: {}", format_ms(self.elapsed(time)))).size(20.0));
+            ui.label(
+                RichText::new(format!("Hedef: {}/{}", self.splits.len(), TARGET_COUNT)).size(20.0),
+            );
+            if self.misses > 0 {
+                ui.label(
+                    RichText::new(format!("Iska: {} (+{} sn)", self.misses, self.misses))
+                        .size(16.0)
+                        .color(egui::Color32::RED),
+                );
+            }
+
+            for target in &self.targets {
+                let tex_key = match target.state {
+                    TargetState::Alive => target.texture_key.as_str(),
+                    TargetState::Dying(t0) => {
+                        if now.duration_since(t0) < Duration::from_millis(250) {
+                            "assets/sprite/enemy_death-1.png"
+                        } else {
+                            "assets/sprite/enemy_death-2.png"
+                        }
+                    }
+                };
+                if let Some(texture) = self.textures.get(tex_key) {
+                    let rect = egui::Rect::from_min_size(
+                        egui::pos2(target.x, target.y),
+                        egui::vec2(display_width, display_height),
+                    );
+                    ui.painter().image(
+                        texture.id(),
+                        rect,
+                        egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                        egui::Color32::WHITE,
+                    );
+                }
+            }
+
+            if let Some(t0) = self.last_miss
+                && now.duration_since(t0) < Duration::from_millis(600)
+            {
+                ui.painter().text(
+                    egui::pos2(400.0, 60.0),
+                    egui::Align2::CENTER_CENTER,
+                    format!("+{} sn", MISS_PENALTY.as_secs()),
+                    egui::FontId::proportional(28.0),
+                    egui::Color32::RED,
+                );
+            }
+        });
+
+        if !self.show_intro && self.finish_time.is_none() {
+            let clicked_pos = ctx.input(|i| {
+                if i.pointer.primary_clicked() {
+                    i.pointer.interact_pos()
+                } else {
+                    None
+                }
+            });
+
+            if let Some(pos) = clicked_pos {
+                self.play_sound("assets/sound/gunshot.mp3");
+                self.handle_shot(pos, now, time, display_width, display_height);
+            }
+
+            ctx.output_mut(|o| o.cursor_icon = egui::CursorIcon::None);
+            if let Some(pos) = ctx.input(|i| i.pointer.hover_pos()) {
+                let painter = ctx.layer_painter(egui::LayerId::new(
+                    egui::Order::Foreground,
+                    egui::Id::new("crosshair"),
+                ));
+                let size = 15.0;
+                let gap = 5.0;
+                let color = egui::Color32::RED;
+                painter.line_segment(
+                    [
+                        pos - egui::vec2(size + gap, 0.0),
+                        pos - egui::vec2(gap, 0.0),
+                    ],
+                    (2.0, color),
+                );
+                painter.line_segment(
+                    [
+                        pos + egui::vec2(gap, 0.0),
+                        pos + egui::vec2(size + gap, 0.0),
+                    ],
+                    (2.0, color),
+                );
+                painter.line_segment(
+                    [
+                        pos - egui::vec2(0.0, size + gap),
+                        pos - egui::vec2(0.0, gap),
+                    ],
+                    (2.0, color),
+                );
+                painter.line_segment(
+                    [
+                        pos + egui::vec2(0.0, gap),
+                        pos + egui::vec2(0.0, size + gap),
+                    ],
+                    (2.0, color),
+                );
+            }
+
+            ctx.request_repaint();
+        } else {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        signal
+    }
+
+    fn elapsed(&self, time: f64) -> Duration {
+        Duration::from_secs_f64((time - self.start_secs).max(0.0)) + MISS_PENALTY * self.misses
+    }
+
+    fn handle_shot(
+        &mut self,
+        pos: egui::Pos2,
+        shot_time: Instant,
+        time: f64,
+        width: f32,
+        height: f32,
+    ) {
+        let hit = self.targets.iter_mut().find(|target| {
+            matches!(target.state, TargetState::Alive)
+                && egui::Rect::from_min_size(
+                    egui::pos2(target.x, target.y),
+                    egui::vec2(width, height),
+                )
+                .contains(pos)
+        });
+
+        self.tally.shot(hit.is_some());
+        self.tally.heat.shot(pos, hit.is_some());
+        let Some(target) = hit else {
+            self.misses += 1;
+            self.last_miss = Some(shot_time);
+            return;
+        };
+
+        target.state = TargetState::Dying(shot_time);
+        self.tally.kill(EnemyType::Normal);
+        self.splits.push(self.elapsed(time));
+
+        let index = rand::thread_rng().gen_range(1..=3);
+        self.play_sound(&format!("assets/sound/enemy_death-{}.wav", index));
+
+        if self.splits.len() == TARGET_COUNT {
+            self.finish_time = Some(shot_time);
+            self.save_record();
+        }
+    }
+
+    fn save_record(&mut self) {
+        let total = self.splits.last().map(|split| split.as_millis() as u64);
+        let best = self.record.best_splits_ms.last().copied();
+        if total.is_some_and(|total| best.is_none_or(|best| total < best)) {
+            self.record.best_splits_ms = self
+                .splits
+                .iter()
+                .map(|split| split.as_millis() as u64)
+                .collect();
+            self.new_record = true;
+            storage::save_toml(RECORD_FILE, &self.record);
+        }
+    }
+
+    fn draw_results(&self, ui: &mut egui::Ui, signal: &mut Option<String>) {
+        let total = self.splits.last().copied().unwrap_or_default();
+        ui.vertical_centered(|ui| {
+            ui.add_space(30.0);
+            ui.heading(RichText::new("Zamana Karşı - Bitti!").size(32.0));
+            ui.add_space(10.0);
+            ui.label(RichText::new(format!("Toplam Süre: {}", format_ms(total))).size(24.0));
+            ui.label(
+                RichText::new(format!(
+                    "Iska: {} (+{})",
+                    self.misses,
+                    format_ms(MISS_PENALTY * self.misses)
+                ))
+                .size(18.0)
+                .color(egui::Color32::RED),
+            );
+            if self.new_record {
+                ui.label(
+                    RichText::new("Yeni Rekor!")
+                        .size(22.0)
+                        .color(egui::Color32::GOLD),
+                );
+            }
+            ui.add_space(10.0);
+
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("time_attack_splits")
+                        .striped(true)
+                        .spacing([30.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label(RichText::new("Hedef").strong());
+                            ui.label(RichText::new("Ara").strong());
+                            ui.label(RichText::new("Toplam").strong());
+                            ui.label(RichText::new("Rekor Farkı").strong());
+                            ui.end_row();
+
+                            let mut previous = Duration::ZERO;
+                            for (index, split) in self.splits.iter().enumerate() {
+                                ui.label(format!("{}", index + 1));
+                                ui.label(format_ms(split.saturating_sub(previous)));
+                                ui.label(format_ms(*split));
+                                match self.record.best_splits_ms.get(index) {
+                                    Some(&best) if !self.new_record => {
+                                        let diff = split.as_millis() as i64 - best as i64;
+                                        let color = if diff <= 0 {
+                                            egui::Color32::GREEN
+                                        } else {
+                                            egui::Color32::RED
+                                        };
+                                        ui.label(
+                                            RichText::new(format!("{:+.3}", diff as f64 / 1000.0))
+                                                .color(color),
+                                        );
+                                    }
+                                    _ => {
+                                        ui.label("-");
+                                    }
+                                }
+                                ui.end_row();
+                                previous = *split;
+                            }
+                        });
+                });
+
+            ui.add_space(20.0);
+            if ui.button(RichText::new("Tekrar Oyna").size(20.0)).clicked() {
+                *signal = Some("restart".to_string());
+            }
+            ui.add_space(10.0);
+            if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                *signal = Some("menu".to_string());
+            }
+        });
+    }
+
+    fn spawn_target(&mut self) {
+        let mut rng = rand::thread_rng();
+        let texture_key = format!("assets/sprite/enemy-{}.png", rng.gen_range(1..=2));
+        self.targets.push(Target {
+            x: rng.gen_range(0.0..725.0),
+            y: rng.gen_range(100.0..480.0),
+            texture_key,
+            state: TargetState::Alive,
+        });
+        self.spawned += 1;
+    }
+
+    fn play_sound(&self, path: &str) {
+        if let Ok(file) = std::fs::File::open(path)
+            && let Ok(source) = Decoder::new(BufReader::new(file))
+            && let Ok(sink) = Sink::try_new(&self.audio_handle)
+        {
+            sink.append(source);
+            sink.detach();
+        }
+    }
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{}.{:03} sn", duration.as_secs(), duration.subsec_millis())
+}
+
+fn load_image(ctx: &egui::Context, path: &str) -> TextureHandle {
+    let img = ImageReader::open(path).unwrap().decode().unwrap();
+    let rgba = img.to_rgba8();
+    let (width, height) = img.dimensions();
+    let color_image =
+        egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &rgba);
+    ctx.load_texture(path, color_image, TextureOptions::default())
+}
@@ -0,0 +1,643 @@
+use crate::difficulty::Difficulty;
+use crate::profile::{RoundSummary, RoundTally};
+use crate::storage;
+use eframe::egui;
+use egui::{Color32, RichText};
+use rand::Rng;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+use std::time::{Duration, Instant};
+
+const HISTORY_FILE: &str = "trainer.toml";
+const HISTORY_LIMIT: usize = 50;
+const CHART_POINTS: usize = 20;
+
+const FLICK_TARGETS: u32 = 30;
+const FLICK_COLUMNS: usize = 5;
+const FLICK_ROWS: usize = 3;
+const TRACKING_LENGTH: Duration = Duration::from_secs(30);
+const TRACKING_RADIUS: f32 = 35.0;
+const REACTION_ROUNDS: u32 = 10;
+const TARGET_RADIUS: f32 = 28.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum TrainerKind {
+    Flick,
+    Tracking,
+    Reaction,
+}
+
+const ALL_KINDS: [TrainerKind; 3] = [
+    TrainerKind::Flick,
+    TrainerKind::Tracking,
+    TrainerKind::Reaction,
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct SessionResult {
+    value: f32,
+    accuracy: f32,
+    count: u32,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct TrainerHistory {
+    #[serde(default)]
+    flick: Vec<SessionResult>,
+    #[serde(default)]
+    tracking: Vec<SessionResult>,
+    #[serde(default)]
+    reaction: Vec<SessionResult>,
+}
+
+enum Drill {
+    Flick {
+        cell: usize,
+        shown_at: Instant,
+        hits: u32,
+        shots: u32,
+        total: Duration,
+    },
+    Tracking {
+        pos: egui::Pos2,
+        velocity: egui::Vec2,
+        next_turn: Instant,
+        hitpoints: f32,
+        on_target: Duration,
+        drained: u32,
+        start: Instant,
+    },
+    Reaction {
+        round: u32,
+        show_at: Instant,
+        pos: egui::Pos2,
+        early: u32,
+        total: Duration,
+        early_at: Option<Instant>,
+    },
+}
+
+enum Screen {
+    Select,
+    Running(TrainerKind, Drill),
+    Results(TrainerKind, SessionResult),
+}
+
+pub struct TrainerApp {
+    screen: Screen,
+    history: TrainerHistory,
+    last_update: Instant,
+    started: Instant,
+    summary: Option<RoundSummary>,
+    #[allow(dead_code)]
+    audio_stream: OutputStream,
+    audio_handle: OutputStreamHandle,
+}
+
+impl TrainerKind {
+    fn name(&self) -> &'static str {
+        match self {
+            TrainerKind::Flick => "Izgara Flick",
+            TrainerKind::Tracking => "Takip",
+            TrainerKind::Reaction => "Refleks",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            TrainerKind::Flick => "Izgarada rastgele beliren hedefleri hızla vur",
+            TrainerKind::Tracking => "İmleci hareketli hedefin üzerinde tutarak canını erit",
+            TrainerKind::Reaction => "Hedef belirdiği an tıkla, tepki süren ölçülsün",
+        }
+    }
+
+    fn metric(&self, value: f32) -> String {
+        match self {
+            TrainerKind::Flick | TrainerKind::Reaction => format!("{:.0} ms", value),
+            TrainerKind::Tracking => format!("%{:.1}", value),
+        }
+    }
+
+    fn count_label(&self) -> &'static str {
+        match self {
+            TrainerKind::Flick => "Vurulan hedef",
+            TrainerKind::Tracking => "Eritilen hedef",
+            TrainerKind::Reaction => "Geçerli tur",
+        }
+    }
+
+    fn lower_is_better(&self) -> bool {
+        !matches!(self, TrainerKind::Tracking)
+    }
+}
+
+impl TrainerHistory {
+    fn sessions(&self, kind: TrainerKind) -> &Vec<SessionResult> {
+        match kind {
+            TrainerKind::Flick => &self.flick,
+            TrainerKind::Tracking => &self.tracking,
+            TrainerKind::Reaction => &self.reaction,
+        }
+    }
+
+    fn push(&mut self, kind: TrainerKind, result: SessionResult) {
+        let sessions = match kind {
+            TrainerKind::Flick => &mut self.flick,
+            TrainerKind::Tracking => &mut self.tracking,
+            TrainerKind::Reaction => &mut self.reaction,
+        };
+        sessions.push(result);
+        if sessions.len() > HISTORY_LIMIT {
+            sessions.remove(0);
+        }
+        storage::save_toml(HISTORY_FILE, self);
+    }
+}
+
+impl Drill {
+    fn new(kind: TrainerKind, now: Instant) -> Self {
+        let mut rng = rand::thread_rng();
+        match kind {
+            TrainerKind::Flick => Drill::Flick {
+                cell: rng.gen_range(0..FLICK_COLUMNS * FLICK_ROWS),
+                shown_at: now,
+                hits: 0,
+                shots: 0,
+                total: Duration::ZERO,
+            },
+            TrainerKind::Tracking => Drill::Tracking {
+                pos: egui::pos2(400.0, 330.0),
+                velocity: random_velocity(),
+                next_turn: now + Duration::from_secs(1),
+                hitpoints: 100.0,
+                on_target: Duration::ZERO,
+                drained: 0,
+                start: now,
+            },
+            TrainerKind::Reaction => Drill::Reaction {
+                round: 0,
+                show_at: now + reaction_delay(),
+                pos: random_point(),
+                early: 0,
+                total: Duration::ZERO,
+                early_at: None,
+            },
+        }
+    }
+}
+
+impl Drill {
+    fn tally(&self) -> RoundTally {
+        let (shots, hits) = match *self {
+            Drill::Flick { hits, shots, .. } => (shots, hits),
+            Drill::Tracking { .. } => (0, 0),
+            Drill::Reaction { round, early, .. } => (round + early, round),
+        };
+        RoundTally {
+            shots,
+            hits,
+            ..RoundTally::default()
+        }
+    }
+}
+
+impl TrainerApp {
+    pub fn new() -> Self {
+        let (stream, stream_handle) = OutputStream::try_default().unwrap();
+        Self {
+            screen: Screen::Select,
+            history: storage::load_toml(HISTORY_FILE),
+            last_update: Instant::now(),
+            started: Instant::now(),
+            summary: None,
+            audio_stream: stream,
+            audio_handle: stream_handle,
+        }
+    }
+
+    pub fn take_summary(&mut self) -> Option<RoundSummary> {
+        self.summary.take()
+    }
+
+    pub fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) -> Option<String> {
+        let now = Instant::now();
+        let delta = now.duration_since(self.last_update);
+        self.last_update = now;
+        let mut signal = None;
+
+        let hover = ctx.input(|i| i.pointer.hover_pos());
+        let clicked = ctx.input(|i| i.pointer.primary_clicked());
+
+        egui::CentralPanel::default().show(ctx, |ui| match &mut self.screen {
+            Screen::Select => {
+                if let Some(kind) = self.draw_select(ui, &mut signal) {
+                    self.started = now;
+                    self.screen = Screen::Running(kind, Drill::new(kind, now));
+                }
+            }
+            Screen::Running(kind, drill) => {
+                let kind = *kind;
+                let finished = match drill {
+                    Drill::Flick { .. } => {
+                        run_flick(ui, drill, now, clicked.then_some(hover).flatten())
+                    }
+                    Drill::Tracking { .. } => run_tracking(ui, drill, now, delta, hover),
+                    Drill::Reaction { .. } => run_reaction(ui, drill, now, clicked),
+                };
+                let tally = drill.tally();
+                if clicked {
+                    self.play_sound("assets/sound/gunshot.mp3");
+                }
+                if let Some(result) = finished {
+                    self.summary = Some(RoundSummary {
+                        mode: format!("Antrenman: {}", kind.name()),
+                        difficulty: Difficulty::default(),
+                        score: result.count,
+                        secs: now.duration_since(self.started).as_secs_f32(),
+                        timed: false,
+                        tally,
+                    });
+                    self.history.push(kind, result);
+                    self.screen = Screen::Results(kind, result);
+                }
+            }
+            Screen::Results(kind, result) => {
+                let (kind, result) = (*kind, *result);
+                ui.vertical_centered(|ui| {
+                    ui.add_space(40.0);
+                    ui.heading(RichText::new(format!("{} - Sonuç", kind.name())).size(32.0));
+                    ui.add_space(10.0);
+                    ui.label(RichText::new(kind.metric(result.value)).size(28.0));
+                    ui.label(
+                        RichText::new(format!("İsabet: %{:.0}", result.accuracy * 100.0))
+                            .size(18.0),
+                    );
+                    ui.label(
+                        RichText::new(format!("{}: {}", kind.count_label(), result.count))
+                            .size(18.0),
+                    );
+                    ui.add_space(10.0);
+                    draw_trend(ui, kind, self.history.sessions(kind));
+                    ui.add_space(20.0);
+                    if ui.button(RichText::new("Tekrar").size(20.0)).clicked() {
+                        self.started = now;
+                        self.screen = Screen::Running(kind, Drill::new(kind, now));
+                    }
+                    ui.add_space(10.0);
+                    if ui
+                        .button(RichText::new("Antrenman Menüsü").size(20.0))
+                        .clicked()
+                    {
+                        self.screen = Screen::Select;
+                    }
+                });
+            }
+        });
+
+        if let Screen::Running(..) = self.screen {
+            ctx.output_mut(|o| o.cursor_icon = egui::CursorIcon::Crosshair);
+            ctx.request_repaint();
+        } else {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        signal
+    }
+
+    fn draw_select(&self, ui: &mut egui::Ui, signal: &mut Option<String>) -> Option<TrainerKind> {
+        let mut selected = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(20.0);
+                ui.heading(RichText::new("Antrenman").size(36.0));
+                ui.add_space(10.0);
+
+                for kind in ALL_KINDS {
+                    ui.add_space(10.0);
+                    let button = ui.add_sized(
+                        [200.0, 36.0],
+                        egui::Button::new(RichText::new(kind.name()).size(20.0)),
+                    );
+                    if button.clicked() {
+                        selected = Some(kind);
+                    }
+                    ui.label(
+                        RichText::new(kind.description())
+                            .italics()
+                            .size(14.0)
+                            .color(Color32::GRAY),
+                    );
+                    draw_trend(ui, kind, self.history.sessions(kind));
+                }
+
+                ui.add_space(20.0);
+                if ui.button(RichText::new("Menüye Dön").size(20.0)).clicked() {
+                    *signal = Some("menu".to_string());
+                }
+            });
+        });
+        selected
+    }
+
+    fn play_sound(&self, path: &str) {
+        if let Ok(file) = std::fs::File::open(path)
+            && let Ok(source) = Decoder::new(BufReader::new(file))
+            && let Ok(sink) = Sink::try_new(&self.audio_handle)
+        {
+            sink.append(source);
+            sink.detach();
+        }
+    }
+}
+
+fn run_flick(
+    ui: &mut egui::Ui,
+    drill: &mut Drill,
+    now: Instant,
+    shot: Option<egui::Pos2>,
+) -> Option<SessionResult> {
+    let Drill::Flick {
+        cell,
+        shown_at,
+        hits,
+        shots,
+        total,
+    } = drill
+    else {
+        return None;
+    };
+
+    let center = flick_cell_center(*cell);
+    if let Some(pos) = shot {
+        *shots += 1;
+        if pos.distance(center) <= TARGET_RADIUS {
+            *hits += 1;
+            *total += now.duration_since(*shown_at);
+            let mut rng = rand::thread_rng();
+            let mut next = *cell;
+            while next == *cell {
+                next = rng.gen_range(0..FLICK_COLUMNS * FLICK_ROWS);
+            }
+            *cell = next;
+            *shown_at = now;
+        }
+    }
+
+    if *hits >= FLICK_TARGETS {
+        return Some(SessionResult {
+            value: total.as_secs_f32() * 1000.0 / *hits as f32,
+            accuracy: *hits as f32 / (*shots).max(1) as f32,
+            count: *hits,
+        });
+    }
+
+    ui.label(RichText::new(format!("Hedef: {}/{}", hits, FLICK_TARGETS)).size(20.0));
+    for index in 0..FLICK_COLUMNS * FLICK_ROWS {
+        ui.painter().circle_stroke(
+            flick_cell_center(index),
+            6.0,
+            (1.0, Color32::from_gray(200)),
+        );
+    }
+    draw_target(ui, flick_cell_center(*cell), TARGET_RADIUS);
+    None
+}
+
+fn run_tracking(
+    ui: &mut egui::Ui,
+    drill: &mut Drill,
+    now: Instant,
+    delta: Duration,
+    hover: Option<egui::Pos2>,
+) -> Option<SessionResult> {
+    let Drill::Tracking {
+        pos,
+        velocity,
+        next_turn,
+        hitpoints,
+        on_target,
+        drained,
+        start,
+    } = drill
+    else {
+        return None;
+    };
+
+    let elapsed = now.duration_since(*start);
+    if elapsed >= TRACKING_LENGTH {
+        let ratio = on_target.as_secs_f32() / TRACKING_LENGTH.as_secs_f32();
+        return Some(SessionResult {
+            value: ratio * 100.0,
+            accuracy: ratio,
+            count: *drained,
+        });
+    }
+
+    if now >= *next_turn {
+        *velocity = random_velocity();
+        *next_turn = now + Duration::from_secs_f32(rand::thread_rng().gen_range(0.8..=1.5));
+    }
+    *pos += *velocity * delta.as_secs_f32();
+    if pos.x < 60.0 || pos.x > 740.0 {
+        velocity.x = -velocity.x;
+        pos.x = pos.x.clamp(60.0, 740.0);
+    }
+    if pos.y < 120.0 || pos.y > 540.0 {
+        velocity.y = -velocity.y;
+        pos.y = pos.y.clamp(120.0, 540.0);
+    }
+
+    let tracking = hover.is_some_and(|hover| hover.distance(*pos) <= TRACKING_RADIUS);
+    if tracking {
+        *on_target += delta;
+        *hitpoints -= 60.0 * delta.as_secs_f32();
+        if *hitpoints <= 0.0 {
+            *drained += 1;
+            *hitpoints = 100.0;
+            *pos = random_point();
+        }
+    }
+
+    let remaining = TRACKING_LENGTH.saturating_sub(elapsed);
+    ui.label(RichText::new(format!("Süre: {:.1}", remaining.as_secs_f32())).size(20.0));
+    ui.label(RichText::new(format!("Eritilen: {}", drained)).size(20.0));
+
+    draw_target(ui, *pos, TRACKING_RADIUS);
+    let bar = egui::Rect::from_center_size(*pos - egui::vec2(0.0, 50.0), egui::vec2(70.0, 8.0));
+    ui.painter().rect_filled(bar, 2.0, Color32::from_gray(60));
+    ui.painter().rect_filled(
+        egui::Rect::from_min_size(bar.min, egui::vec2(bar.width() * *hitpoints / 100.0, 8.0)),
+        2.0,
+        if tracking {
+            Color32::GREEN
+        } else {
+            Color32::DARK_RED
+        },
+    );
+    None
+}
+
+fn run_reaction(
+    ui: &mut egui::Ui,
+    drill: &mut Drill,
+    now: Instant,
+    clicked: bool,
+) -> Option<SessionResult> {
+    let Drill::Reaction {
+        round,
+        show_at,
+        pos,
+        early,
+        total,
+        early_at,
+    } = drill
+    else {
+        return None;
+    };
+
+    let visible = now >= *show_at;
+    if clicked {
+        if visible {
+            *total += now.duration_since(*show_at);
+            *round += 1;
+        } else {
+            *early += 1;
+            *early_at = Some(now);
+        }
+        *show_at = now + reaction_delay();
+        *pos = random_point();
+    }
+
+    if *round >= REACTION_ROUNDS {
+        return Some(SessionResult {
+            value: total.as_secs_f32() * 1000.0 / *round as f32,
+            accuracy: *round as f32 / (*round + *early) as f32,
+            count: *round,
+        });
+    }
+
+    ui.label(RichText::new(format!("Tur: {}/{}", *round + 1, REACTION_ROUNDS)).size(20.0));
+    if now >= *show_at {
+        draw_target(ui, *pos, TARGET_RADIUS);
+    } else if early_at.is_some_and(|t0| now.duration_since(t0) < Duration::from_millis(800)) {
+        ui.painter().text(
+            egui::pos2(400.0, 300.0),
+            egui::Align2::CENTER_CENTER,
+            "Erken!",
+            egui::FontId::proportional(36.0),
+            Color32::RED,
+        );
+    } else {
+        ui.painter().text(
+            egui::pos2(400.0, 300.0),
+            egui::Align2::CENTER_CENTER,
+            "Bekle...",
+            egui::FontId::proportional(28.0),
+            Color32::GRAY,
+        );
+    }
+    None
+}
+
+fn draw_trend(ui: &mut egui::Ui, kind: TrainerKind, sessions: &[SessionResult]) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(360.0, 90.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 4.0, Color32::from_gray(235));
+
+    let recent = &sessions[sessions.len().saturating_sub(CHART_POINTS)..];
+    if recent.is_empty() {
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "Henüz kayıt yok",
+            egui::FontId::proportional(14.0),
+            Color32::GRAY,
+        );
+        return;
+    }
+
+    let values: Vec<f32> = recent.iter().map(|session| session.value).collect();
+    let min = values.iter().copied().fold(f32::MAX, f32::min);
+    let max = values.iter().copied().fold(f32::MIN, f32::max);
+    let span = (max - min).max(1.0);
+    let plot = rect.shrink2(egui::vec2(40.0, 12.0));
+    let step = plot.width() / (CHART_POINTS - 1) as f32;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            egui::pos2(
+                plot.left() + i as f32 * step,
+                plot.bottom() - (value - min) / span * plot.height(),
+            )
+        })
+        .collect();
+
+    painter.add(egui::Shape::line(
+        points.clone(),
+        (2.0, Color32::from_rgb(60, 120, 220)),
+    ));
+    let best = if kind.lower_is_better() { min } else { max };
+    for (point, value) in points.iter().zip(&values) {
+        let color = if *value == best {
+            Color32::GOLD
+        } else {
+            Color32::from_rgb(60, 120, 220)
+        };
+        painter.circle_filled(*point, 3.0, color);
+    }
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 4.0),
+        egui::Align2::LEFT_TOP,
+        kind.metric(max),
+        egui::FontId::proportional(11.0),
+        Color32::DARK_GRAY,
+    );
+    painter.text(
+        rect.left_bottom() + egui::vec2(4.0, -4.0),
+        egui::Align2::LEFT_BOTTOM,
+        kind.metric(min),
+        egui::FontId::proportional(11.0),
+        Color32::DARK_GRAY,
+    );
+    painter.text(
+        rect.right_top() + egui::vec2(-4.0, 4.0),
+        egui::Align2::RIGHT_TOP,
+        format!("En iyi: {}", kind.metric(best)),
+        egui::FontId::proportional(11.0),
+        Color32::DARK_GRAY,
+    );
+}
+
+fn draw_target(ui: &egui::Ui, center: egui::Pos2, radius: f32) {
+    let painter = ui.painter();
+    painter.circle_filled(center, radius, Color32::from_rgb(220, 40, 40));
+    painter.circle_filled(center, radius * 0.66, Color32::WHITE);
+    painter.circle_filled(center, radius * 0.33, Color32::from_rgb(220, 40, 40));
+}
+
+fn flick_cell_center(index: usize) -> egui::Pos2 {
+    let column = index % FLICK_COLUMNS;
+    let row = index / FLICK_COLUMNS;
+    let cell = egui::vec2(680.0 / FLICK_COLUMNS as f32, 420.0 / FLICK_ROWS as f32);
+    egui::pos2(
+        60.0 + cell.x * (column as f32 + 0.5),
+        120.0 + cell.y * (row as f32 + 0.5),
+    )
+}
+
+fn random_point() -> egui::Pos2 {
+    let mut rng = rand::thread_rng();
+    egui::pos2(rng.gen_range(80.0..720.0), rng.gen_range(140.0..520.0))
+}
+
+fn random_velocity() -> egui::Vec2 {
+    let mut rng = rand::thread_rng();
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let speed = rng.gen_range(180.0..320.0);
+    egui::vec2(angle.cos(), angle.sin()) * speed
+}
+
+fn reaction_delay() -> Duration {
+    Duration::from_secs_f32(rand::thread_rng().gen_range(1.0..=3.0))
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub struct SimParams {
+    pub supply_lifetime: Duration,
+    pub elite_damage: i64,
+    pub max_time: i64,
+    pub magazine_size: u32,
+    pub reload_time: Duration,
+    pub tnt_radius: f32,
+}
+
+impl Default for SimParams {
+    fn default() -> Self {
+        SimParams {
+            supply_lifetime: Duration::from_secs(3),
+            elite_damage: 3,
+            max_time: 60,
+            magazine_size: 8,
+            reload_time: Duration::from_millis(1200),
+            tnt_radius: 180.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Perk {
+    SupplyLifetime,
+    TntRadius,
+    EliteArmor,
+    ExtraTime,
+    FastReload,
+    BigMagazine,
+}
+
+pub const ALL_PERKS: [Perk; 6] = [
+    Perk::SupplyLifetime,
+    Perk::TntRadius,
+    Perk::EliteArmor,
+    Perk::ExtraTime,
+    Perk::FastReload,
+    Perk::BigMagazine,
+];
+
+impl Perk {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perk::SupplyLifetime => "Dayanıklı Kutular",
+            Perk::TntRadius => "Geniş Patlama",
+            Perk::EliteArmor => "Çelik Yelek",
+            Perk::ExtraTime => "Fazla Mesai",
+            Perk::FastReload => "Seri Şarjör",
+            Perk::BigMagazine => "Geniş Şarjör",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Perk::SupplyLifetime => "Destek kutuları +1 sn ekranda kalır",
+            Perk::TntRadius => "TNT patlama yarıçapı +40 piksel",
+            Perk::EliteArmor => "Elit atışları 1 sn daha az süre götürür",
+            Perk::ExtraTime => "Süre sınırı +15 sn artar",
+            Perk::FastReload => "Şarjör değiştirme %25 hızlanır",
+            Perk::BigMagazine => "Şarjöre +3 mermi",
+        }
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            Perk::EliteArmor => 2,
+            _ => 3,
+        }
+    }
+
+    pub fn cost(&self, level: u32) -> u32 {
+        let base = match self {
+            Perk::SupplyLifetime => 10,
+            Perk::TntRadius => 15,
+            Perk::EliteArmor => 20,
+            Perk::ExtraTime => 15,
+            Perk::FastReload => 10,
+            Perk::BigMagazine => 12,
+        };
+        base * (level + 1)
+    }
+
+    fn apply(&self, params: &mut SimParams) {
+        match self {
+            Perk::SupplyLifetime => params.supply_lifetime += Duration::from_secs(1),
+            Perk::TntRadius => params.tnt_radius += 40.0,
+            Perk::EliteArmor => params.elite_damage = (params.elite_damage - 1).max(1),
+            Perk::ExtraTime => params.max_time += 15,
+            Perk::FastReload => params.reload_time = params.reload_time.mul_f32(0.75),
+            Perk::BigMagazine => params.magazine_size += 3,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct UpgradeStack {
+    perks: Vec<Perk>,
+}
+
+impl UpgradeStack {
+    pub fn level(&self, perk: Perk) -> u32 {
+        self.perks.iter().filter(|&&owned| owned == perk).count() as u32
+    }
+
+    pub fn next_cost(&self, perk: Perk) -> Option<u32> {
+        let level = self.level(perk);
+        (level < perk.max_level()).then(|| perk.cost(level))
+    }
+
+    pub fn push(&mut self, perk: Perk) {
+        self.perks.push(perk);
+    }
+
+    pub fn params(&self) -> SimParams {
+        let mut params = SimParams::default();
+        for perk in &self.perks {
+            perk.apply(&mut params);
+        }
+        params
+    }
+}